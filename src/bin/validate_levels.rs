@@ -0,0 +1,185 @@
+//! # validate_levels
+//!
+//! Checks every level file and level pack under `assets/levels` (or the directory given as
+//! the first argument) and exits non-zero if any of them is broken.
+//!
+//! With the `solve` subcommand it instead searches every level for solutions and exits
+//! non-zero if any level has none, or has solutions other than its `correct_connections`.
+//! A level that leaves its connections out must have exactly one solution. MatchConnections
+//! levels are solved by their `correct_connections` and nothing else, so for them only
+//! whether those can be drawn is checked.
+//!
+//! Usage: `cargo run --bin validate_levels [solve] [levels_dir]`
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use echonet_game::levels::{
+    is_level_pack_file, level_files_in_dir, level_pack_files_in_dir, parse_level, parse_level_pack,
+    raw_connection_lists, LEVELS_DIR,
+};
+use echonet_game::resources::{Objective, PuzzleSpec};
+use echonet_game::solver::find_solutions;
+use echonet_game::validation::{check_duplicate_connections, check_level};
+
+// Unintended solutions listed per level before the rest are only counted
+const SOLUTIONS_SHOWN: usize = 3;
+
+// Returns one line per problem found in the file; an empty list means the file is fine.
+fn check_file(path: &Path) -> Vec<String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) => return vec![format!("could not read file: {}", err)],
+    };
+
+    let levels: Vec<PuzzleSpec> = if is_level_pack_file(path) {
+        match parse_level_pack(path, &contents) {
+            Ok(levels) => levels,
+            Err(err) => return vec![err.to_string()],
+        }
+    } else {
+        match parse_level(path, &contents) {
+            Ok(level) => vec![level],
+            Err(err) => return vec![err.to_string()],
+        }
+    };
+    let raw_connections = match raw_connection_lists(path, &contents) {
+        Ok(lists) => lists,
+        Err(err) => return vec![err.to_string()],
+    };
+
+    let mut problems = Vec::new();
+    for (idx, level) in levels.iter().enumerate() {
+        let label = if is_level_pack_file(path) {
+            format!("level {} ({:?}): ", idx, level.title)
+        } else {
+            String::new()
+        };
+
+        let duplicates = raw_connections
+            .get(idx)
+            .map(|connections| check_duplicate_connections(connections))
+            .unwrap_or_default();
+        for issue in duplicates.iter().chain(check_level(level).iter()) {
+            problems.push(format!("{}{}", label, issue));
+        }
+    }
+    problems
+}
+
+fn read_levels(path: &Path) -> Result<Vec<PuzzleSpec>, String> {
+    let contents = fs::read_to_string(path).map_err(|err| format!("could not read file: {}", err))?;
+    let levels = if is_level_pack_file(path) {
+        parse_level_pack(path, &contents)
+    } else {
+        parse_level(path, &contents).map(|level| vec![level])
+    };
+    levels.map_err(|err| err.to_string())
+}
+
+// Returns one line per level that does not have exactly its intended solution, or exactly
+// one solution when it has no `correct_connections` to compare with. Counts the
+// MatchConnections levels, whose uniqueness is not searched for, in `match_connections`.
+fn solve_file(path: &Path, match_connections: &mut usize) -> Vec<String> {
+    let levels = match read_levels(path) {
+        Ok(levels) => levels,
+        Err(err) => return vec![err],
+    };
+
+    let mut problems = Vec::new();
+    for (idx, level) in levels.iter().enumerate() {
+        let label = format!("level {} ({:?}): ", idx, level.title);
+        if level.objective == Objective::MatchConnections {
+            *match_connections += 1;
+        }
+        let report = match find_solutions(level, SOLUTIONS_SHOWN + 1) {
+            Ok(report) => report,
+            Err(err) => {
+                problems.push(format!("{}{}", label, err));
+                continue;
+            }
+        };
+        if report.solutions.is_empty() {
+            problems.push(format!("{}no solution", label));
+            continue;
+        }
+        let more = if report.truncated { " or more" } else { "" };
+        if level.correct_connections.is_empty() {
+            // Nothing to compare against; all that can be checked is that the answer is unique
+            if !report.is_unique() {
+                problems.push(format!("{}{}{} solutions, expected exactly one", label, report.solutions.len(), more));
+                for solution in report.solutions.iter().take(SOLUTIONS_SHOWN) {
+                    problems.push(format!("  {:?}", solution));
+                }
+            }
+            continue;
+        }
+        let unintended: Vec<&Vec<(usize, usize)>> = report.unintended(level).collect();
+        if unintended.is_empty() {
+            continue;
+        }
+        problems.push(format!("{}{}{} unintended solution(s)", label, unintended.len(), more));
+        for solution in unintended.into_iter().take(SOLUTIONS_SHOWN) {
+            problems.push(format!("  {:?}", solution));
+        }
+    }
+    problems
+}
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let solve = args.first().is_some_and(|arg| arg == "solve");
+    if solve {
+        args.remove(0);
+    }
+    let levels_dir = args
+        .first()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("assets").join(LEVELS_DIR));
+
+    let mut files = match level_files_in_dir(&levels_dir) {
+        Ok(files) => files,
+        Err(err) => {
+            eprintln!("{}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    files.extend(level_pack_files_in_dir(&levels_dir).unwrap_or_default());
+
+    if files.is_empty() {
+        eprintln!("No level files found in {}", levels_dir.display());
+        return ExitCode::FAILURE;
+    }
+
+    let mut broken_files = 0;
+    let mut match_connections = 0;
+    for path in &files {
+        let problems = if solve { solve_file(path, &mut match_connections) } else { check_file(path) };
+        if problems.is_empty() {
+            println!("ok    {}", path.display());
+        } else {
+            broken_files += 1;
+            println!("FAIL  {}", path.display());
+            for problem in problems {
+                println!("      - {}", problem);
+            }
+        }
+    }
+
+    println!();
+    println!("{} file(s) checked, {} broken", files.len(), broken_files);
+    if match_connections > 0 {
+        println!(
+            "{} MatchConnections level(s) skipped for uniqueness: only their correct_connections \
+             solve them, so they are unique by definition and were only checked for being drawable",
+            match_connections
+        );
+    }
+    if broken_files > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,545 @@
+//! The puzzle rules as plain data, independent of Bevy's windowing and rendering.
+//!
+//! A `PuzzleBoard` is built from a `PuzzleSpec` and tracks which connections the player
+//! has drawn. Gameplay systems translate input into calls on the board and mirror the
+//! result into `Connection` entities; tools and tests can drive it directly.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use bevy::math::Vec2;
+
+use crate::components::{CONNECTION_HIT_DISTANCE, NODE_HIT_RADIUS};
+use crate::propagation::propagate_echo;
+use crate::resources::{NodeKind, Objective, Obstacle, PuzzleSpec};
+
+const CABLE_TOLERANCE: f32 = 0.01;
+
+/// Undirected connections are stored smallest id first; see `PuzzleBoard::connection_key`.
+pub fn normalize_pair(a: usize, b: usize) -> (usize, usize) {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+/// Shortest distance from `point` to the line segment between `start` and `end`.
+pub fn distance_to_segment(point: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let segment = end - start;
+    let length_squared = segment.length_squared();
+    if length_squared == 0.0 {
+        return point.distance(start);
+    }
+    let t = ((point - start).dot(segment) / length_squared).clamp(0.0, 1.0);
+    point.distance(start + segment * t)
+}
+
+/// Whether segments `p1`-`p2` and `q1`-`q2` cross at a point inside both of them.
+/// Segments that only touch, or that run along each other, do not count.
+pub fn segments_cross(p1: Vec2, p2: Vec2, q1: Vec2, q2: Vec2) -> bool {
+    let d1 = (p2 - p1).perp_dot(q1 - p1);
+    let d2 = (p2 - p1).perp_dot(q2 - p1);
+    let d3 = (q2 - q1).perp_dot(p1 - q1);
+    let d4 = (q2 - q1).perp_dot(p2 - q1);
+    d1 * d2 < 0.0 && d3 * d4 < 0.0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConnectError {
+    UnknownNode(usize),
+    SelfLoop(usize),
+    AlreadyConnected(usize, usize),
+    // Node kind rules
+    Blocked(usize),
+    SourceCannotReceive(usize),
+    SinkCannotSend(usize),
+    DegreeLimit { node: usize, limit: usize },
+    // No-crossing levels
+    Crosses(usize, usize),
+    PassesThroughNode(usize),
+    // Index into the level's obstacle list
+    BlockedByObstacle(usize),
+    // Cable budget levels, in world units
+    OverBudget { length: f32, remaining: f32 },
+    // Not part of the solution, refused under WrongConnectionPolicy::Reject. The board
+    // itself never returns this; the policy is applied by the gameplay systems
+    Wrong(usize, usize),
+}
+
+impl fmt::Display for ConnectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectError::UnknownNode(id) => write!(f, "node {} does not exist", id),
+            ConnectError::SelfLoop(id) => write!(f, "node {} cannot connect to itself", id),
+            ConnectError::AlreadyConnected(a, b) => write!(f, "{}-{} is already connected", a, b),
+            ConnectError::Blocked(id) => write!(f, "node {} is a blocker and cannot be connected", id),
+            ConnectError::SourceCannotReceive(id) => write!(f, "node {} is a source; connections can only start there", id),
+            ConnectError::SinkCannotSend(id) => write!(f, "node {} is a sink; connections can only end there", id),
+            ConnectError::DegreeLimit { node, limit } => {
+                write!(f, "node {} already has its {} connection(s)", node, limit)
+            }
+            ConnectError::Crosses(a, b) => write!(f, "it would cross connection {}-{}", a, b),
+            ConnectError::PassesThroughNode(id) => write!(f, "it would pass through node {}", id),
+            ConnectError::BlockedByObstacle(idx) => write!(f, "obstacle {} is in the way", idx),
+            ConnectError::OverBudget { length, remaining } => {
+                write!(f, "it needs {:.0} units of cable but only {:.0} are left", length, remaining)
+            }
+            ConnectError::Wrong(a, b) => write!(f, "{}-{} is not part of the solution", a, b),
+        }
+    }
+}
+
+impl std::error::Error for ConnectError {}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PuzzleBoard {
+    node_positions: Vec<Vec2>,
+    correct_connections: HashSet<(usize, usize)>,
+    drawn_connections: HashSet<(usize, usize)>,
+    objective: Objective,
+    // Connections run from the first node of each pair to the second
+    directed: bool,
+    node_kinds: Vec<NodeKind>,
+    required_degrees: Vec<Option<usize>>,
+    no_crossing: bool,
+    obstacles: Vec<Obstacle>,
+    cable_budget: Option<f32>,
+}
+
+impl PuzzleBoard {
+    pub fn new(spec: &PuzzleSpec) -> Self {
+        PuzzleBoard {
+            node_positions: spec.node_positions.clone(),
+            correct_connections: spec
+                .correct_connections
+                .iter()
+                .map(|&(a, b)| if spec.directed { (a, b) } else { normalize_pair(a, b) })
+                .collect(),
+            drawn_connections: HashSet::new(),
+            objective: spec.objective.clone(),
+            directed: spec.directed,
+            node_kinds: spec.node_kinds.clone(),
+            required_degrees: spec.required_degrees.clone(),
+            no_crossing: spec.no_crossing,
+            obstacles: spec.obstacles.clone(),
+            cable_budget: spec.cable_budget,
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// How a connection from `a` to `b` is stored: as given on directed boards,
+    /// normalized on undirected ones.
+    pub fn connection_key(&self, a: usize, b: usize) -> (usize, usize) {
+        if self.directed { (a, b) } else { normalize_pair(a, b) }
+    }
+
+    /// The drawn connection between `a` and `b` in whichever direction it was drawn.
+    pub fn connection_between(&self, a: usize, b: usize) -> Option<(usize, usize)> {
+        [(a, b), (b, a)]
+            .into_iter()
+            .map(|(from, to)| self.connection_key(from, to))
+            .find(|key| self.drawn_connections.contains(key))
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.node_positions.len()
+    }
+
+    pub fn node_position(&self, id: usize) -> Option<Vec2> {
+        self.node_positions.get(id).copied()
+    }
+
+    pub fn node_kind(&self, id: usize) -> NodeKind {
+        self.node_kinds.get(id).copied().unwrap_or_default()
+    }
+
+    /// Number of drawn connections touching `id`, in either direction.
+    pub fn degree(&self, id: usize) -> usize {
+        self.drawn_connections
+            .iter()
+            .filter(|&&(a, b)| a == id || b == id)
+            .count()
+    }
+
+    /// How many connections the level asks node `id` to end up with, if it says.
+    pub fn required_degree(&self, id: usize) -> Option<usize> {
+        self.required_degrees.get(id).copied().flatten()
+    }
+
+    /// Connections node `id` still needs to reach its required degree.
+    pub fn remaining_degree(&self, id: usize) -> Option<usize> {
+        self.required_degree(id)
+            .map(|required| required.saturating_sub(self.degree(id)))
+    }
+
+    // Most connections node `id` may have, from its kind and its required degree
+    fn degree_limit(&self, id: usize) -> Option<usize> {
+        let kind_limit = match self.node_kind(id) {
+            NodeKind::Source => Some(1),
+            NodeKind::Splitter(n) => Some(n),
+            NodeKind::Blocker => Some(0),
+            NodeKind::Relay | NodeKind::Sink => None,
+        };
+        match (kind_limit, self.required_degree(id)) {
+            (Some(kind_limit), Some(required)) => Some(kind_limit.min(required)),
+            (limit, None) | (None, limit) => limit,
+        }
+    }
+
+    /// The node whose hit circle contains `point`, if any. When hit circles overlap
+    /// the closest node wins.
+    pub fn node_at(&self, point: Vec2) -> Option<usize> {
+        self.node_positions
+            .iter()
+            .enumerate()
+            .map(|(id, pos)| (id, pos.distance(point)))
+            .filter(|&(_, distance)| distance < NODE_HIT_RADIUS)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(id, _)| id)
+    }
+
+    /// The drawn connection whose line passes within `CONNECTION_HIT_DISTANCE` of `point`,
+    /// if any. When several do, the closest wins.
+    pub fn connection_at(&self, point: Vec2) -> Option<(usize, usize)> {
+        self.drawn_connections
+            .iter()
+            .filter_map(|&(a, b)| {
+                let start = self.node_position(a)?;
+                let end = self.node_position(b)?;
+                Some(((a, b), distance_to_segment(point, start, end)))
+            })
+            .filter(|&(_, distance)| distance <= CONNECTION_HIT_DISTANCE)
+            .min_by(|x, y| x.1.total_cmp(&y.1).then(x.0.cmp(&y.0)))
+            .map(|(pair, _)| pair)
+    }
+
+    pub fn drawn_connections(&self) -> &HashSet<(usize, usize)> {
+        &self.drawn_connections
+    }
+
+    pub fn correct_connections(&self) -> &HashSet<(usize, usize)> {
+        &self.correct_connections
+    }
+
+    pub fn objective(&self) -> &Objective {
+        &self.objective
+    }
+
+    /// Whether `a`-`b` is one of the connections the level wants drawn. On directed
+    /// boards it must also run from `a` to `b`.
+    pub fn is_correct(&self, a: usize, b: usize) -> bool {
+        self.correct_connections.contains(&self.connection_key(a, b))
+    }
+
+    /// Whether drawing `a`-`b` counts as a mistake. Only levels solved by matching
+    /// `correct_connections` can tell; echo objectives accept any network that works.
+    pub fn is_wrong(&self, a: usize, b: usize) -> bool {
+        self.objective == Objective::MatchConnections && !self.is_correct(a, b)
+    }
+
+    /// Whether `a` and `b` are connected, in either direction.
+    pub fn is_connected(&self, a: usize, b: usize) -> bool {
+        self.connection_between(a, b).is_some()
+    }
+
+    /// Checks that `a`-`b` could be drawn right now, without drawing it. Two nodes can
+    /// share at most one connection, whatever its direction. Returns the pair as it would
+    /// be stored (see `connection_key`).
+    pub fn check_connect(&self, a: usize, b: usize) -> Result<(usize, usize), ConnectError> {
+        for id in [a, b] {
+            if id >= self.node_count() {
+                return Err(ConnectError::UnknownNode(id));
+            }
+        }
+        if a == b {
+            return Err(ConnectError::SelfLoop(a));
+        }
+        if let Some(existing) = self.connection_between(a, b) {
+            return Err(ConnectError::AlreadyConnected(existing.0, existing.1));
+        }
+        for id in [a, b] {
+            if self.node_kind(id) == NodeKind::Blocker {
+                return Err(ConnectError::Blocked(id));
+            }
+        }
+        if self.node_kind(b) == NodeKind::Source {
+            return Err(ConnectError::SourceCannotReceive(b));
+        }
+        if self.node_kind(a) == NodeKind::Sink {
+            return Err(ConnectError::SinkCannotSend(a));
+        }
+        for id in [a, b] {
+            if let Some(limit) = self.degree_limit(id) {
+                if self.degree(id) >= limit {
+                    return Err(ConnectError::DegreeLimit { node: id, limit });
+                }
+            }
+        }
+        if let (Some(start), Some(end)) = (self.node_position(a), self.node_position(b)) {
+            if let Some(idx) = self.obstacle_in_the_way(start, end) {
+                return Err(ConnectError::BlockedByObstacle(idx));
+            }
+        }
+        if let (Some(remaining), Some(length)) = (self.remaining_cable(), self.connection_length(a, b)) {
+            // Allow for rounding so a solution that uses the budget exactly still fits
+            if length > remaining + CABLE_TOLERANCE {
+                return Err(ConnectError::OverBudget { length, remaining });
+            }
+        }
+        if self.no_crossing {
+            self.check_no_crossing(a, b)?;
+        }
+        Ok(self.connection_key(a, b))
+    }
+
+    /// Straight-line distance between two nodes, which is what a connection between them costs.
+    pub fn connection_length(&self, a: usize, b: usize) -> Option<f32> {
+        Some(self.node_position(a)?.distance(self.node_position(b)?))
+    }
+
+    /// Total length of every drawn connection.
+    pub fn cable_used(&self) -> f32 {
+        self.drawn_connections
+            .iter()
+            .filter_map(|&(a, b)| self.connection_length(a, b))
+            .sum()
+    }
+
+    pub fn cable_budget(&self) -> Option<f32> {
+        self.cable_budget
+    }
+
+    /// Cable left to draw with, or None when the level has no budget.
+    pub fn remaining_cable(&self) -> Option<f32> {
+        self.cable_budget.map(|budget| (budget - self.cable_used()).max(0.0))
+    }
+
+    /// Index of the first obstacle a straight line from `start` to `end` runs into.
+    pub fn obstacle_in_the_way(&self, start: Vec2, end: Vec2) -> Option<usize> {
+        self.obstacles
+            .iter()
+            .position(|obstacle| obstacle.blocks_segment(start, end))
+    }
+
+    // On no-crossing levels a new segment may not cross a drawn connection or pass
+    // through the hit circle of any node other than its own ends
+    fn check_no_crossing(&self, a: usize, b: usize) -> Result<(), ConnectError> {
+        let (Some(start), Some(end)) = (self.node_position(a), self.node_position(b)) else {
+            return Ok(());
+        };
+        for (id, &pos) in self.node_positions.iter().enumerate() {
+            if id != a && id != b && distance_to_segment(pos, start, end) < NODE_HIT_RADIUS {
+                return Err(ConnectError::PassesThroughNode(id));
+            }
+        }
+        let mut drawn: Vec<(usize, usize)> = self.drawn_connections.iter().copied().collect();
+        drawn.sort_unstable();
+        for (c, d) in drawn {
+            // Connections sharing a node meet there rather than crossing
+            if c == a || c == b || d == a || d == b {
+                continue;
+            }
+            if let (Some(other_start), Some(other_end)) = (self.node_position(c), self.node_position(d)) {
+                if segments_cross(start, end, other_start, other_end) {
+                    return Err(ConnectError::Crosses(c, d));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws a connection from `a` to `b`, returning the stored pair.
+    pub fn try_connect(&mut self, a: usize, b: usize) -> Result<(usize, usize), ConnectError> {
+        let pair = self.check_connect(a, b)?;
+        self.drawn_connections.insert(pair);
+        Ok(pair)
+    }
+
+    /// Redraws a previously stored connection. Undirected pairs are stored without the
+    /// drag direction, so if `a` to `b` breaks a node kind rule, `b` to `a` is tried too.
+    pub fn restore_connection(&mut self, a: usize, b: usize) -> Result<(usize, usize), ConnectError> {
+        match self.try_connect(a, b) {
+            Err(err) if !self.directed => self.try_connect(b, a).map_err(|_| err),
+            result => result,
+        }
+    }
+
+    /// Removes the connection between `a` and `b`, whichever way it runs.
+    /// Returns false if it was not drawn.
+    pub fn disconnect(&mut self, a: usize, b: usize) -> bool {
+        match self.connection_between(a, b) {
+            Some(key) => self.drawn_connections.remove(&key),
+            None => false,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.drawn_connections.clear();
+    }
+
+    /// Whether every splitter and every node with a required degree has exactly
+    /// that many connections.
+    pub fn degrees_satisfied(&self) -> bool {
+        (0..self.node_count()).all(|id| {
+            let splitter_ok = match self.node_kind(id) {
+                NodeKind::Splitter(n) => self.degree(id) == n,
+                _ => true,
+            };
+            splitter_ok && self.remaining_degree(id).is_none_or(|remaining| remaining == 0)
+        })
+    }
+
+    /// Whether the drawn connections meet the level's objective (exactly the correct
+    /// connections, or a network that carries the echo to its targets on schedule)
+    /// with every splitter and degree requirement fully connected.
+    pub fn is_solved(&self) -> bool {
+        self.degrees_satisfied() && self.objective_met()
+    }
+
+    /// Whether the drawn connections meet the level's objective, ignoring node kinds
+    /// and required degrees.
+    pub fn objective_met(&self) -> bool {
+        match &self.objective {
+            Objective::MatchConnections => self.drawn_connections == self.correct_connections,
+            // The degrees themselves are the goal; is_solved checks them
+            Objective::MatchDegrees => true,
+            Objective::Goal(goal) => goal.is_met(self.node_count(), &self.drawn_connections),
+            Objective::EchoOrder { source, order } => {
+                let arrivals = propagate_echo(self, *source);
+                let ticks: Option<Vec<u32>> = order.iter().map(|&node| arrivals.arrival_tick(node)).collect();
+                ticks.is_some_and(|ticks| ticks.windows(2).all(|pair| pair[0] < pair[1]))
+            }
+            Objective::EchoTicks { source, arrivals: targets } => {
+                let arrivals = propagate_echo(self, *source);
+                targets.iter().all(|&(node, tick)| arrivals.arrival_tick(node) == Some(tick))
+            }
+        }
+    }
+
+    /// The next step towards a solution: the lowest wrong connection to remove, or else
+    /// the lowest missing one to draw. Wrong connections come first since they can keep a
+    /// missing one from being drawn.
+    ///
+    /// MatchConnections levels are hinted towards `correct_connections`. Any other level
+    /// is hinted towards whichever of `solutions` differs from the drawn connections in
+    /// the fewest places; see `LevelSolutions` for where they come from.
+    /// None once the level is solved, or when there is no solution to aim for.
+    pub fn hint(&self, solutions: &[HashSet<(usize, usize)>]) -> Option<Hint> {
+        if self.objective == Objective::MatchConnections {
+            return self.hint_towards(&self.correct_connections);
+        }
+        if self.is_solved() {
+            return None;
+        }
+        let target = solutions
+            .iter()
+            .min_by_key(|solution| solution.symmetric_difference(&self.drawn_connections).count())?;
+        self.hint_towards(target)
+    }
+
+    fn hint_towards(&self, target: &HashSet<(usize, usize)>) -> Option<Hint> {
+        let wrong = self.drawn_connections.difference(target).min();
+        if let Some(&(a, b)) = wrong {
+            return Some(Hint::Remove(a, b));
+        }
+        target.difference(&self.drawn_connections).min().map(|&(a, b)| Hint::Add(a, b))
+    }
+
+    /// Every connection that could still be drawn, in ascending order. On directed
+    /// boards both directions of each unconnected pair are listed.
+    pub fn candidate_edges(&self) -> Vec<(usize, usize)> {
+        let node_count = self.node_count();
+        let directed = self.directed;
+        (0..node_count)
+            .flat_map(|a| (0..node_count).map(move |b| (a, b)))
+            .filter(|&(a, b)| if directed { a != b } else { a < b })
+            .filter(|&(a, b)| !self.is_connected(a, b))
+            .collect()
+    }
+}
+
+/// What a hint points the player at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    /// A drawn connection that is not part of the solution being hinted at.
+    Remove(usize, usize),
+    /// A connection from the solution being hinted at that has not been drawn yet.
+    Add(usize, usize),
+}
+
+/// A single reversible change to the drawn connections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardAction {
+    Connect(usize, usize),
+    Disconnect(usize, usize),
+}
+
+impl BoardAction {
+    pub fn inverse(self) -> Self {
+        match self {
+            BoardAction::Connect(a, b) => BoardAction::Disconnect(a, b),
+            BoardAction::Disconnect(a, b) => BoardAction::Connect(a, b),
+        }
+    }
+
+    /// Applies the action to `board`. Returns false if the board was left unchanged.
+    pub fn apply(self, board: &mut PuzzleBoard) -> bool {
+        match self {
+            BoardAction::Connect(a, b) => board.restore_connection(a, b).is_ok(),
+            BoardAction::Disconnect(a, b) => board.disconnect(a, b),
+        }
+    }
+}
+
+/// Undo and redo stacks of the actions the player has taken on a board.
+/// Recording a new action discards everything that could have been redone.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ActionHistory {
+    undo_stack: Vec<BoardAction>,
+    redo_stack: Vec<BoardAction>,
+}
+
+impl ActionHistory {
+    pub fn record(&mut self, action: BoardAction) {
+        self.undo_stack.push(action);
+        self.redo_stack.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Reverts the most recent action on `board` and returns it. Returns None, leaving
+    /// the action to undo later, if there is none or the board no longer allows it
+    /// (e.g. redrawing a connection would now go over the cable budget).
+    pub fn undo(&mut self, board: &mut PuzzleBoard) -> Option<BoardAction> {
+        let action = *self.undo_stack.last()?;
+        if !action.inverse().apply(board) {
+            return None;
+        }
+        self.undo_stack.pop();
+        self.redo_stack.push(action);
+        Some(action)
+    }
+
+    /// Re-applies the most recently undone action on `board` and returns it. Returns None,
+    /// leaving the action to redo later, if there is none or the board no longer allows it.
+    pub fn redo(&mut self, board: &mut PuzzleBoard) -> Option<BoardAction> {
+        let action = *self.redo_stack.last()?;
+        if !action.apply(board) {
+            return None;
+        }
+        self.redo_stack.pop();
+        self.undo_stack.push(action);
+        Some(action)
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+}
@@ -1,9 +1,18 @@
 use bevy::prelude::*;
+use crate::board::Hint;
+use crate::resources::{NodeKind, Obstacle};
+
+// Distance from a node's centre within which a click or drag end counts as hitting it
+pub const NODE_HIT_RADIUS: f32 = 25.0;
+
+// Distance from a connection's line within which a right-click counts as hitting it
+pub const CONNECTION_HIT_DISTANCE: f32 = 8.0;
 
 #[derive(Component)]
 pub struct Node {
     pub id: usize,
     pub original_color: Color,
+    pub kind: NodeKind,
 }
 
 #[derive(Component)]
@@ -13,8 +22,49 @@ pub struct ActivatedNode; // Marker component for currently activated node
 pub struct Connection {
     pub start_node_entity: Entity,
     pub end_node_entity: Entity,
+    pub node_ids: (usize, usize), // Pair this entity mirrors from the PuzzleBoard, as the board stores it
+}
+
+#[derive(Component)]
+pub struct ConnectionFlash {
+    pub timer: Timer, // Connection blinks while this runs, then the component is removed
+}
+
+#[derive(Component)]
+pub struct RejectedConnectionFlash {
+    pub node_ids: (usize, usize), // Connection that was refused; it is never drawn on the board
+    pub timer: Timer, // Line blinks while this runs, then the entity is despawned
+}
+
+#[derive(Component)]
+pub struct ObstacleShape(pub Obstacle); // Drawn by obstacle_render_system; blocking is checked on the PuzzleBoard
+
+#[derive(Component)]
+pub struct DegreeBadge {
+    pub node_id: usize, // Node whose remaining required degree this text shows
+}
+
+#[derive(Component)]
+pub struct HintMarker {
+    pub hint: Hint,
+    pub timer: Timer, // Highlight pulses while this runs, then the entity is despawned
 }
 
+#[derive(Component)]
+pub struct HintButton; // HUD button that asks for a hint, like pressing H
+
+#[derive(Component)]
+pub struct HintButtonText; // Label on the hint button showing how many hints are left
+
+#[derive(Component)]
+pub struct LevelTimerText; // HUD clock: time spent, or time left on time-attack levels
+
+#[derive(Component)]
+pub struct MovesText; // HUD move counter, with the limit on move-limit levels
+
+#[derive(Component)]
+pub struct CableBudgetText; // HUD text showing how much of the level's cable budget is left
+
 #[derive(Component)]
 pub struct MainMenuUI; // Marker for main menu UI elements
 
@@ -24,10 +74,17 @@ pub struct GameplayUI; // Marker for gameplay UI elements
 #[derive(Component)]
 pub struct LevelCompleteUI; // Marker for level complete UI elements
 
+#[derive(Component)]
+pub struct LevelFailedUI; // Marker for the time-up screen's UI elements
+
+#[derive(Component)]
+pub struct LevelSelectList; // Main menu column holding one button per authored level
+
 #[derive(Component)]
 pub enum MenuButtonAction {
     Play,
     Quit,
+    SelectLevel(usize),
 }
 
 #[derive(Component)]
@@ -0,0 +1,79 @@
+//! Automatic difficulty rating for a level, worked out from its `PuzzleSpec` alone.
+//!
+//! The rating adds up how much there is to look at (nodes and solution connections), how
+//! many ways lines can get tangled, and how many connections the player has to decide on,
+//! and takes a little off for every other network that also solves the level.
+
+use crate::board::{segments_cross, PuzzleBoard};
+use crate::resources::PuzzleSpec;
+use crate::solver::find_solutions;
+
+// Solutions other than the intended one stop counting towards the rating after this many
+const MAX_ALTERNATIVES: usize = 10;
+
+/// The measurements a difficulty rating is made from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DifficultyBreakdown {
+    pub node_count: usize,
+    /// Connections in the level's `correct_connections`.
+    pub solution_edges: usize,
+    /// Pairs of possible connections, between four different nodes, that would cross.
+    pub crossing_opportunities: usize,
+    /// Solutions besides `correct_connections`, up to `MAX_ALTERNATIVES`. Levels too large
+    /// for the solver count as having the most.
+    pub alternatives: usize,
+    /// Connections the solver has to decide on: every one the empty board accepts, each
+    /// adding a level to its search tree.
+    pub search_depth: usize,
+}
+
+impl DifficultyBreakdown {
+    pub fn of(spec: &PuzzleSpec) -> Self {
+        let board = PuzzleBoard::new(spec);
+        let possible = board.candidate_edges();
+
+        let undirected: Vec<(usize, usize)> = possible.iter().copied().filter(|&(a, b)| a < b).collect();
+        let mut crossing_opportunities = 0;
+        for (idx, &(a, b)) in undirected.iter().enumerate() {
+            for &(c, d) in &undirected[idx + 1..] {
+                if a == c || a == d || b == c || b == d {
+                    continue;
+                }
+                if let (Some(p1), Some(p2), Some(q1), Some(q2)) = (
+                    board.node_position(a),
+                    board.node_position(b),
+                    board.node_position(c),
+                    board.node_position(d),
+                ) {
+                    if segments_cross(p1, p2, q1, q2) {
+                        crossing_opportunities += 1;
+                    }
+                }
+            }
+        }
+
+        let alternatives = match find_solutions(spec, MAX_ALTERNATIVES + 1) {
+            Ok(report) => report.unintended(spec).count().min(MAX_ALTERNATIVES),
+            Err(_) => MAX_ALTERNATIVES,
+        };
+
+        DifficultyBreakdown {
+            node_count: board.node_count(),
+            solution_edges: board.correct_connections().len(),
+            crossing_opportunities,
+            alternatives,
+            search_depth: possible.iter().filter(|&&(a, b)| board.check_connect(a, b).is_ok()).count(),
+        }
+    }
+
+    /// The rating itself; never below 1.
+    pub fn score(&self) -> u32 {
+        let points = 4 * self.node_count + 3 * self.solution_edges + self.crossing_opportunities + self.search_depth;
+        points.saturating_sub(2 * self.alternatives).max(1) as u32
+    }
+}
+
+/// How hard `spec` is to solve; see `DifficultyBreakdown` for what goes into it.
+pub fn difficulty(spec: &PuzzleSpec) -> u32 {
+    DifficultyBreakdown::of(spec).score()
+}
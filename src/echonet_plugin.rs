@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+use crate::game_state::GameState;
+use crate::gameplay_plugin::GameplayPlugin;
+use crate::menu_plugin::MenuPlugin;
+use crate::resources::{CurrentLevel, GameFont, LevelManager, LevelSource, WrongConnectionPolicy};
+use crate::ui_plugin::UiPlugin;
+
+/// Options for embedding EchoNet that do not depend on the window it runs in.
+#[derive(Debug, Clone)]
+pub struct EchoNetConfig {
+    /// Index of the level played first.
+    pub starting_level: usize,
+    /// Where levels are read from.
+    pub level_source: LevelSource,
+    /// Whether to show the built-in main menu. Without it the game starts straight
+    /// into `starting_level`, and the host app decides what `GameState::MainMenu` shows.
+    pub use_menu: bool,
+    /// Whether connections outside the solution are refused or drawn in a warning color.
+    /// Either way they count as mistakes.
+    pub wrong_connection_policy: WrongConnectionPolicy,
+}
+
+impl Default for EchoNetConfig {
+    fn default() -> Self {
+        EchoNetConfig {
+            starting_level: 0,
+            level_source: LevelSource::default(),
+            use_menu: true,
+            wrong_connection_policy: WrongConnectionPolicy::default(),
+        }
+    }
+}
+
+/// The whole game: states, shared resources, and the menu, gameplay and UI plugins.
+/// Add it after `DefaultPlugins` (or an equivalent set with windowing and rendering).
+#[derive(Default)]
+pub struct EchoNetPlugin {
+    pub config: EchoNetConfig,
+}
+
+impl Plugin for EchoNetPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .insert_resource(CurrentLevel {
+                level_id: self.config.starting_level,
+                total_levels: 0,
+            })
+            .insert_resource(self.config.level_source.clone())
+            .insert_resource(self.config.wrong_connection_policy)
+            .init_resource::<LevelManager>();
+
+        if self.config.use_menu {
+            app
+                .init_state::<GameState>()
+                .add_plugins(MenuPlugin);
+        } else {
+            // The main menu normally inserts GameFont when it is first shown
+            app
+                .insert_state(GameState::LoadingLevel)
+                .insert_resource(GameFont(default()));
+        }
+
+        app.add_plugins((GameplayPlugin, UiPlugin));
+    }
+}
@@ -7,4 +7,5 @@ pub enum GameState {
     LoadingLevel, // Intermediary state to setup levels
     Playing,
     LevelComplete,
+    LevelFailed, // The level's time limit ran out
 }
@@ -1,7 +1,9 @@
 use bevy::prelude::*; // Added
-use crate::components::{Node, Connection, ActivatedNode};
-use crate::resources::PlayerAttempt;
-use super::ConnectionAttemptEvent; 
+use std::collections::HashSet;
+use crate::board::ConnectError;
+use crate::components::{Node, Connection, ActivatedNode, ConnectionFlash, GameplayUI, RejectedConnectionFlash, NODE_HIT_RADIUS};
+use crate::resources::{MistakeCounter, PlayerAttempt, WrongConnectionPolicy};
+use super::{ConnectionAttemptEvent, ConnectionRejectedEvent, ConnectionRemovedEvent}; 
 
 #[derive(Resource, Default)]
 pub struct DragState {
@@ -17,6 +19,7 @@ pub fn draw_connection_system(
     camera_q: Query<(&Camera, &GlobalTransform)>,
     node_query: Query<(Entity, &Transform, &Node)>, 
     activated_q: Query<(Entity, &Node), With<ActivatedNode>>, 
+    player_attempt: Res<PlayerAttempt>,
     mut drag_state: Local<DragState>,
     mut gizmos: Gizmos, 
     mut connection_attempt_writer: EventWriter<ConnectionAttemptEvent>,
@@ -34,8 +37,7 @@ pub fn draw_connection_system(
             // Use single() as per deprecation warning for get_single() on Query
             if let Ok((activated_entity, activated_node_comp)) = activated_q.single() {
                  if let Ok((_, activated_node_transform, _)) = node_query.get(activated_entity) {
-                    let distance = world_pos.distance(activated_node_transform.translation.truncate());
-                    if distance < 25.0 { 
+                    if player_attempt.board.node_at(world_pos) == Some(activated_node_comp.id) { 
                         drag_state.start_node_entity = Some(activated_entity);
                         drag_state.start_node_id = Some(activated_node_comp.id);
                         drag_state.current_mouse_pos = activated_node_transform.translation.truncate(); 
@@ -48,7 +50,18 @@ pub fn draw_connection_system(
         if mouse_button_input.pressed(MouseButton::Left) {
             if let Some(start_entity_val) = drag_state.start_node_entity {
                 if let Ok((_, start_node_transform, _)) = node_query.get(start_entity_val) {
-                     gizmos.line_2d(start_node_transform.translation.truncate(), drag_state.current_mouse_pos, Color::srgb(1.0, 1.0, 0.0));
+                    let start = start_node_transform.translation.truncate();
+                    // Red while the straight line to the cursor runs into an obstacle
+                    let preview_color = if player_attempt.board.obstacle_in_the_way(start, drag_state.current_mouse_pos).is_some() {
+                        Color::srgb(1.0, 0.0, 0.0)
+                    } else {
+                        Color::srgb(1.0, 1.0, 0.0)
+                    };
+                    if player_attempt.board.is_directed() {
+                        gizmos.arrow_2d(start, drag_state.current_mouse_pos, preview_color);
+                    } else {
+                        gizmos.line_2d(start, drag_state.current_mouse_pos, preview_color);
+                    }
                 } else { 
                     drag_state.start_node_entity = None;
                     drag_state.start_node_id = None;
@@ -58,22 +71,15 @@ pub fn draw_connection_system(
 
         if mouse_button_input.just_released(MouseButton::Left) {
             if let (Some(start_entity_val), Some(start_node_id_val)) = (drag_state.start_node_entity, drag_state.start_node_id) {
-                let mut end_node_found = false;
-                for (end_entity, end_node_transform, end_node_comp) in node_query.iter() {
-                    if start_entity_val == end_entity { continue; } 
-
-                    let distance = world_pos.distance(end_node_transform.translation.truncate());
-                    if distance < 25.0 { 
-                        println!("Attempting connection between {} and {}", start_node_id_val, end_node_comp.id);
-                        connection_attempt_writer.write(ConnectionAttemptEvent {
-                            node1_id: start_node_id_val,
-                            node2_id: end_node_comp.id,
-                        });
-                        end_node_found = true;
-                        break;
-                    }
+                let end_node_id = player_attempt.board.node_at(world_pos).filter(|&id| id != start_node_id_val);
+                if let Some(end_node_id) = end_node_id {
+                    println!("Attempting connection between {} and {}", start_node_id_val, end_node_id);
+                    connection_attempt_writer.write(ConnectionAttemptEvent {
+                        node1_id: start_node_id_val,
+                        node2_id: end_node_id,
+                    });
                 }
-                if end_node_found {
+                if end_node_id.is_some() {
                      println!("Connection drawn (event sent).");
                 } else {
                     println!("Connection attempt failed - no end node found on release.");
@@ -86,70 +92,218 @@ pub fn draw_connection_system(
     }
 }
 
+fn node_entity_by_id(node_query: &Query<(Entity, &Node)>, id: usize) -> Option<Entity> {
+    node_query.iter().find(|(_, node_comp)| node_comp.id == id).map(|(entity, _)| entity)
+}
+
 pub fn check_connection_attempt_system(
-    mut commands: Commands,
     mut connection_events: EventReader<ConnectionAttemptEvent>,
     mut player_attempt: ResMut<PlayerAttempt>,
-    node_query: Query<(Entity, &Node)>, 
-    existing_connections: Query<&Connection>,
+    mut mistake_counter: ResMut<MistakeCounter>,
+    wrong_connection_policy: Res<WrongConnectionPolicy>,
+    mut rejected_writer: EventWriter<ConnectionRejectedEvent>,
 ) {
     for event in connection_events.read() {
-        let (id1, id2) = if event.node1_id < event.node2_id {
-            (event.node1_id, event.node2_id)
-        } else {
-            (event.node2_id, event.node1_id)
+        let (id1, id2) = match player_attempt.board.check_connect(event.node1_id, event.node2_id) {
+            Ok(pair) => pair,
+            Err(err) => {
+                println!("Connection {}-{} rejected: {}", event.node1_id, event.node2_id, err);
+                rejected_writer.write(ConnectionRejectedEvent {
+                    node1_id: event.node1_id,
+                    node2_id: event.node2_id,
+                    reason: err,
+                });
+                continue;
+            }
         };
+        if player_attempt.board.is_wrong(id1, id2) {
+            mistake_counter.mistakes += 1;
+            println!("Connection {}-{} is not part of the solution (mistakes: {})", id1, id2, mistake_counter.mistakes);
+            if *wrong_connection_policy == WrongConnectionPolicy::Reject {
+                rejected_writer.write(ConnectionRejectedEvent {
+                    node1_id: event.node1_id,
+                    node2_id: event.node2_id,
+                    reason: ConnectError::Wrong(id1, id2),
+                });
+                continue;
+            }
+        }
 
-        let already_drawn_by_player = player_attempt.drawn_connections.contains(&(id1, id2));
-        
-        let mut connection_component_exists = false;
-        for conn_comp in existing_connections.iter() {
-            if let (Ok((_, n1_comp)), Ok((_, n2_comp))) = (node_query.get(conn_comp.start_node_entity), node_query.get(conn_comp.end_node_entity)) {
-                let (exist_id1, exist_id2) = if n1_comp.id < n2_comp.id {(n1_comp.id, n2_comp.id)} else {(n2_comp.id, n1_comp.id)};
-                if exist_id1 == id1 && exist_id2 == id2 {
-                    connection_component_exists = true;
-                    break;
-                }
+        // The board owns the rules; Connection entities only mirror what it accepted
+        match player_attempt.connect(event.node1_id, event.node2_id) {
+            Ok(_) => {
+                println!("Player connections: {:?}", player_attempt.board.drawn_connections());
+            }
+            Err(err) => {
+                println!("Connection {}-{} rejected: {}", event.node1_id, event.node2_id, err);
             }
         }
+    }
+}
+
+/// Erases the connection under the cursor while the right mouse button is held, so a
+/// right-click removes one edge and a right-drag sweeps away every edge it passes over.
+pub fn remove_connection_system(
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window>,
+    camera_q: Query<(&Camera, &GlobalTransform)>,
+    mut player_attempt: ResMut<PlayerAttempt>,
+    mut connection_removed_writer: EventWriter<ConnectionRemovedEvent>,
+) {
+    if !mouse_button_input.pressed(MouseButton::Right) {
+        return;
+    }
+    let Ok(window) = windows.single() else { return; };
+    let Ok((camera, camera_transform)) = camera_q.single() else { return; };
 
-        if !already_drawn_by_player && !connection_component_exists {
-            player_attempt.drawn_connections.insert((id1, id2));
-            println!("Player connections: {:?}", player_attempt.drawn_connections);
+    let Some(world_pos) = window.cursor_position()
+        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor).ok())
+        .map(|ray| ray.origin.truncate())
+    else {
+        return;
+    };
 
-            let mut node_entities: [Option<Entity>; 2] = [None, None];
-            for (entity, node_comp) in node_query.iter() {
-                if node_comp.id == id1 { node_entities[0] = Some(entity); }
-                else if node_comp.id == id2 { node_entities[1] = Some(entity); }
-            }
+    if let Some((id1, id2)) = player_attempt.board.connection_at(world_pos) {
+        player_attempt.disconnect(id1, id2);
+        println!("Connection {}-{} removed. Player connections: {:?}", id1, id2, player_attempt.board.drawn_connections());
+        connection_removed_writer.write(ConnectionRemovedEvent { node1_id: id1, node2_id: id2 });
+    }
+}
 
-            if let (Some(e1), Some(e2)) = (node_entities[0], node_entities[1]) {
-                 commands.spawn((
-                    Connection { start_node_entity: e1, end_node_entity: e2 },
-                 )).insert(Name::new(format!("ConnectionComp_{}-{}", id1, id2)));
-                 println!("Connection component spawned for {}-{}", id1, id2);
+/// Spawns and despawns `Connection` entities so there is exactly one per connection
+/// drawn on the board, whichever system changed the board (drawing, undo, redo, reload).
+pub fn sync_connection_entities_system(
+    mut commands: Commands,
+    player_attempt: Res<PlayerAttempt>,
+    connection_query: Query<(Entity, &Connection)>,
+    node_query: Query<(Entity, &Node)>,
+) {
+    if !player_attempt.is_changed() {
+        return;
+    }
+    let drawn = player_attempt.board.drawn_connections();
+
+    let mut mirrored: HashSet<(usize, usize)> = HashSet::new();
+    for (entity, connection) in connection_query.iter() {
+        if !drawn.contains(&connection.node_ids) || !mirrored.insert(connection.node_ids) {
+            commands.entity(entity).despawn();
+            println!("Connection component despawned for {}-{}", connection.node_ids.0, connection.node_ids.1);
+        }
+    }
+
+    for &(id1, id2) in drawn.difference(&mirrored) {
+        if let (Some(e1), Some(e2)) = (node_entity_by_id(&node_query, id1), node_entity_by_id(&node_query, id2)) {
+             commands.spawn((
+                Connection { start_node_entity: e1, end_node_entity: e2, node_ids: (id1, id2) },
+             )).insert(Name::new(format!("ConnectionComp_{}-{}", id1, id2)));
+             println!("Connection component spawned for {}-{}", id1, id2);
+        }
+    }
+}
+
+// Connections that are not part of the solution stand out from the green correct ones
+const WRONG_CONNECTION_COLOR: Color = Color::srgb(1.0, 0.35, 0.2);
+const FLASH_COLOR: Color = Color::srgb(1.0, 0.1, 0.1);
+const FLASH_SECONDS: f32 = 0.8;
+const FLASH_BLINKS_PER_SECOND: f32 = 6.0;
+
+/// Makes the connection that blocked an attempt on a no-crossing level blink, and
+/// flashes a wrong connection refused under `WrongConnectionPolicy::Reject` in its place.
+pub fn flash_blocking_connection_system(
+    mut commands: Commands,
+    mut rejected_events: EventReader<ConnectionRejectedEvent>,
+    connection_query: Query<(Entity, &Connection)>,
+) {
+    for event in rejected_events.read() {
+        match event.reason {
+            ConnectError::Crosses(a, b) => {
+                if let Some((entity, _)) = connection_query.iter().find(|(_, connection)| connection.node_ids == (a, b)) {
+                    commands.entity(entity).insert(ConnectionFlash {
+                        timer: Timer::from_seconds(FLASH_SECONDS, TimerMode::Once),
+                    });
+                }
             }
-        } else {
-            println!("Connection {}-{} already attempted or component exists.", id1, id2);
+            ConnectError::Wrong(a, b) => {
+                commands.spawn((
+                    RejectedConnectionFlash {
+                        node_ids: (a, b),
+                        timer: Timer::from_seconds(FLASH_SECONDS, TimerMode::Once),
+                    },
+                    Name::new(format!("RejectedConnection_{}-{}", a, b)),
+                    GameplayUI,
+                ));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Blinks each refused wrong connection between its nodes, then despawns it.
+pub fn rejected_connection_flash_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut flash_query: Query<(Entity, &mut RejectedConnectionFlash)>,
+    player_attempt: Res<PlayerAttempt>,
+    mut gizmos: Gizmos,
+) {
+    for (entity, mut flash) in flash_query.iter_mut() {
+        if flash.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if !((flash.timer.elapsed_secs() * FLASH_BLINKS_PER_SECOND) as u32).is_multiple_of(2) {
+            continue;
+        }
+        let (a, b) = flash.node_ids;
+        if let (Some(start), Some(end)) = (player_attempt.board.node_position(a), player_attempt.board.node_position(b)) {
+            gizmos.line_2d(start, end, FLASH_COLOR);
+        }
+    }
+}
+
+pub fn tick_connection_flash_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut flash_query: Query<(Entity, &mut ConnectionFlash)>,
+) {
+    for (entity, mut flash) in flash_query.iter_mut() {
+        if flash.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<ConnectionFlash>();
         }
     }
 }
 
 pub fn persistent_connection_render_system(
-    connection_query: Query<&Connection>, 
+    connection_query: Query<(&Connection, Option<&ConnectionFlash>)>, 
     node_transform_query: Query<(&Transform, &Node)>, 
+    player_attempt: Res<PlayerAttempt>,
     mut gizmos: Gizmos,
 ) {
-    for connection in connection_query.iter() {
+    for (connection, flash) in connection_query.iter() {
+        let (id1, id2) = connection.node_ids;
+        let blink_on = flash.is_some_and(|flash| {
+            ((flash.timer.elapsed_secs() * FLASH_BLINKS_PER_SECOND) as u32).is_multiple_of(2)
+        });
+        let color = if blink_on {
+            FLASH_COLOR
+        } else if player_attempt.board.is_wrong(id1, id2) {
+            WRONG_CONNECTION_COLOR
+        } else {
+            Color::srgb(0.0, 1.0, 0.0)
+        };
         if let (Ok((start_transform, _)), Ok((end_transform, _))) = (
             node_transform_query.get(connection.start_node_entity),
             node_transform_query.get(connection.end_node_entity)
         ) {
-            gizmos.line_2d(
-                start_transform.translation.truncate(),
-                end_transform.translation.truncate(),
-                Color::srgb(0.0, 1.0, 0.0), 
-            );
+            let start = start_transform.translation.truncate();
+            let end = end_transform.translation.truncate();
+            if player_attempt.board.is_directed() {
+                // Stop the arrow at the edge of the target node so its head stays visible
+                let tip = end - (end - start).normalize_or_zero() * NODE_HIT_RADIUS;
+                gizmos.arrow_2d(start, tip, color);
+            } else {
+                gizmos.line_2d(start, end, color);
+            }
         }
     }
 }
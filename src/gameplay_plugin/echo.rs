@@ -1,57 +1,77 @@
 use bevy::prelude::*; // Added
 use crate::components::{Node, ActivatedNode};
+use crate::propagation::{propagate_echo, travel_ticks, ECHO_TICKS_PER_SECOND};
+use crate::resources::{EchoState, PlayerAttempt};
 
-pub fn echo_visualization_system(
-    mut activated_query: Query<(&Node, &mut Sprite), Added<ActivatedNode>>,
-    mut previously_active_nodes: Local<Vec<Entity>>, 
-    mut all_nodes_query: Query<(Entity, &Node, &mut Sprite), Without<ActivatedNode>>, 
+const ACTIVATED_COLOR: Color = Color::srgb(0.8, 0.8, 0.2); // Yellowish for activated
+const ECHO_REACHED_COLOR: Color = Color::srgb(0.3, 0.8, 0.9);
+const ECHO_PULSE_RADIUS: f32 = 6.0;
+
+/// Starts an echo from a node when it is activated, restarts it when the network changes,
+/// and advances it with game time. On levels with an echo objective the echo starts from
+/// the objective's source until the player activates another node.
+pub fn echo_propagation_system(
+    time: Res<Time>,
+    activated_query: Query<&Node, Added<ActivatedNode>>,
+    player_attempt: Res<PlayerAttempt>,
+    mut echo_state: ResMut<EchoState>,
 ) {
-    let mut current_frame_newly_activated_entities = Vec::new();
+    let new_source = activated_query.iter().next().map(|node_comp| node_comp.id);
+    let source = new_source
+        .or_else(|| echo_state.arrivals.as_ref().map(|arrivals| arrivals.source()))
+        .or_else(|| player_attempt.board.objective().echo_source());
 
-    for (activated_node_comp, mut sprite) in activated_query.iter_mut() {
-        println!("Node {} activated, changing color for echo.", activated_node_comp.id);
-        sprite.color = Color::srgb(0.8, 0.8, 0.2); // Corrected: Yellowish for activated
-        
-        for (entity, node_comp_from_all, _) in all_nodes_query.iter() {
-            if node_comp_from_all.id == activated_node_comp.id {
-                current_frame_newly_activated_entities.push(entity);
-                break; 
-            }
+    if let Some(source) = source {
+        if new_source.is_some() || player_attempt.is_changed() || echo_state.arrivals.is_none() {
+            println!("Echo sent from node {}", source);
+            echo_state.arrivals = Some(propagate_echo(&player_attempt.board, source));
+            echo_state.elapsed_ticks = 0.0;
+            return;
         }
     }
 
-    let mut still_active_from_previous = Vec::new();
-    for old_active_entity in previously_active_nodes.iter() {
-        let mut still_has_activated_tag = false;
-        // Check if this old_active_entity is still considered active by some means
-        // (e.g. if it's in current_frame_newly_activated_entities, or if we had a query for With<ActivatedNode>)
-        // For this example, we assume if it's not in current_frame_newly_activated_entities, it might have been deactivated
-        if current_frame_newly_activated_entities.contains(old_active_entity) {
-            still_has_activated_tag = true;
+    if echo_state.arrivals.is_some() {
+        echo_state.elapsed_ticks += time.delta_secs() * ECHO_TICKS_PER_SECOND;
+    }
+}
+
+/// Colors nodes by activation and echo arrival, and draws each pulse moving along its edge.
+pub fn echo_visualization_system(
+    mut node_query: Query<(&Node, &mut Sprite, Has<ActivatedNode>)>,
+    player_attempt: Res<PlayerAttempt>,
+    echo_state: Res<EchoState>,
+    mut gizmos: Gizmos,
+) {
+    for (node_comp, mut sprite, activated) in node_query.iter_mut() {
+        let color = if activated {
+            ACTIVATED_COLOR
+        } else if echo_state.has_reached(node_comp.id) {
+            ECHO_REACHED_COLOR
         } else {
-            // If not newly activated, we infer it might have been deactivated if it was yellow.
-            // This is imperfect. A RemovedComponents<ActivatedNode> system is better.
+            node_comp.original_color
+        };
+        if sprite.color != color {
+            sprite.color = color;
         }
+    }
 
-        if still_has_activated_tag {
-            still_active_from_previous.push(*old_active_entity);
+    let Some(arrivals) = &echo_state.arrivals else { return; };
+    let board = &player_attempt.board;
+    for &(a, b) in board.drawn_connections() {
+        let (Some(pos_a), Some(pos_b)) = (board.node_position(a), board.node_position(b)) else { continue; };
+        let travel = travel_ticks(pos_a, pos_b) as f32;
+        // The echo leaves each end once it arrives there, so an undirected edge can carry two pulses
+        let directions: &[(usize, Vec2, Vec2)] = if board.is_directed() {
+            &[(a, pos_a, pos_b)]
         } else {
-             // If it's not in current_frame_newly_activated_entities, it means ActivatedNode was NOT just added.
-             // If it was yellow (implying it had ActivatedNode), then it must have been removed.
-            if let Ok((_, node_comp, mut sprite)) = all_nodes_query.get_mut(*old_active_entity) {
-                // Only revert if it was actually yellow (activated color)
-                if sprite.color == Color::srgb(0.8, 0.8, 0.2) {
-                    println!("Node {} presumed deactivated (was yellow, not newly activated), reverting color.", node_comp.id);
-                    sprite.color = node_comp.original_color;
-                } else {
-                    // It wasn't yellow, so it might have been deactivated already or never activated.
-                    // Or it's active but wasn't caught by Added query this frame.
-                    still_active_from_previous.push(*old_active_entity); // Keep it if it's not yellow
-                }
+            &[(a, pos_a, pos_b), (b, pos_b, pos_a)]
+        };
+        for &(from, start, end) in directions {
+            let Some(departure) = arrivals.arrival_tick(from) else { continue; };
+            let progress = (echo_state.elapsed_ticks - departure as f32) / travel;
+            if progress > 0.0 && progress < 1.0 {
+                gizmos.circle_2d(start.lerp(end, progress), ECHO_PULSE_RADIUS, ECHO_REACHED_COLOR);
             }
         }
     }
-    
-    // The new list of "active" nodes (for color purposes) are those that were just activated
-    *previously_active_nodes = current_frame_newly_activated_entities;
 }
@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+use crate::board::Hint;
+use crate::components::{GameplayUI, HintButton, HintButtonText, HintMarker};
+use crate::resources::{HintStats, LevelSolutions, PlayerAttempt};
+use super::HintRequestEvent;
+
+const HINT_SECONDS: f32 = 3.0;
+const HINT_PULSES_PER_SECOND: f32 = 2.0;
+const HINT_ADD_COLOR: Color = Color::srgb(1.0, 0.9, 0.3);
+const HINT_REMOVE_COLOR: Color = Color::srgb(1.0, 0.2, 0.8);
+const HINT_BUTTON_COLOR: Color = Color::srgb(0.25, 0.25, 0.35);
+
+fn hint_button_label(remaining: usize) -> String {
+    format!("Hint ({})", remaining)
+}
+
+/// Spawns the HUD hint button in the top-right corner. Called from `setup_level_system`.
+pub fn spawn_hint_button(commands: &mut Commands, remaining: usize) {
+    commands
+        .spawn((
+            Button,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(12.0),
+                right: Val::Px(12.0),
+                padding: UiRect::axes(Val::Px(14.0), Val::Px(6.0)),
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BorderColor(Color::srgb(0.5, 0.5, 0.6)),
+            BackgroundColor(HINT_BUTTON_COLOR),
+            HintButton,
+            GameplayUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text(hint_button_label(remaining)),
+                TextFont {
+                    font_size: 22.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                HintButtonText,
+            ));
+        });
+}
+
+pub fn hint_button_system(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<HintButton>)>,
+    mut hint_request_writer: EventWriter<HintRequestEvent>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            hint_request_writer.write(HintRequestEvent);
+        }
+    }
+}
+
+/// Answers hint requests while the level's hints last, replacing any hint still showing.
+pub fn hint_request_system(
+    mut commands: Commands,
+    mut hint_requests: EventReader<HintRequestEvent>,
+    mut hint_stats: ResMut<HintStats>,
+    player_attempt: Res<PlayerAttempt>,
+    level_solutions: Res<LevelSolutions>,
+    marker_query: Query<Entity, With<HintMarker>>,
+) {
+    for _ in hint_requests.read() {
+        if hint_stats.remaining() == 0 {
+            println!("No hints left for this level.");
+            continue;
+        }
+        let Some(hint) = player_attempt.board.hint(&level_solutions.solutions) else {
+            println!("Nothing to hint: the level is solved or has no solution to aim for.");
+            continue;
+        };
+
+        for entity in marker_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        hint_stats.used += 1;
+        hint_stats.total_used += 1;
+        println!("Hint {:?} ({} left)", hint, hint_stats.remaining());
+        commands.spawn((
+            HintMarker { hint, timer: Timer::from_seconds(HINT_SECONDS, TimerMode::Once) },
+            Name::new("Hint"),
+            GameplayUI,
+        ));
+    }
+}
+
+/// Pulses the hinted connection: yellow for one to draw, magenta with a cross for one
+/// to remove.
+pub fn hint_render_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut marker_query: Query<(Entity, &mut HintMarker)>,
+    player_attempt: Res<PlayerAttempt>,
+    mut gizmos: Gizmos,
+) {
+    for (entity, mut marker) in marker_query.iter_mut() {
+        marker.timer.tick(time.delta());
+        if marker.timer.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let (a, b, color) = match marker.hint {
+            Hint::Add(a, b) => (a, b, HINT_ADD_COLOR),
+            Hint::Remove(a, b) => (a, b, HINT_REMOVE_COLOR),
+        };
+        let board = &player_attempt.board;
+        let (Some(start), Some(end)) = (board.node_position(a), board.node_position(b)) else { continue; };
+        let pulse = 0.5 + 0.5 * (marker.timer.elapsed_secs() * HINT_PULSES_PER_SECOND * std::f32::consts::TAU).cos();
+        let color = color.with_alpha(0.35 + 0.65 * pulse);
+        gizmos.line_2d(start, end, color);
+        if let Hint::Remove(..) = marker.hint {
+            let middle = start.midpoint(end);
+            let size = 12.0;
+            gizmos.line_2d(middle + Vec2::new(-size, -size), middle + Vec2::new(size, size), color);
+            gizmos.line_2d(middle + Vec2::new(-size, size), middle + Vec2::new(size, -size), color);
+        }
+    }
+}
+
+pub fn update_hint_button_text_system(
+    hint_stats: Res<HintStats>,
+    mut text_query: Query<&mut Text, With<HintButtonText>>,
+) {
+    if !hint_stats.is_changed() {
+        return;
+    }
+    for mut text in text_query.iter_mut() {
+        text.0 = hint_button_label(hint_stats.remaining());
+    }
+}
@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext, LoadState};
+use crate::game_state::GameState;
+use crate::levels::{is_level_pack_file, parse_level, parse_level_pack, LevelLoadError, LEVEL_PACK_EXTENSIONS};
+use crate::resources::{CurrentLevel, LevelLibrary, LevelReload, PlayerAttempt, PuzzleSpec};
+
+/// An ordered list of levels loaded from a single `.levelpack.ron` / `.levelpack.json` file,
+/// or the one level of a single-level `.ron` / `.json` file.
+#[derive(Asset, TypePath, Debug)]
+pub struct LevelPack {
+    pub levels: Vec<PuzzleSpec>,
+}
+
+// Pack extensions first: they are the more specific match for `.levelpack.ron` files
+const LEVEL_FILE_EXTENSIONS: &[&str] = &[LEVEL_PACK_EXTENSIONS[0], LEVEL_PACK_EXTENSIONS[1], "ron", "json"];
+
+#[derive(Default)]
+pub struct LevelPackLoader;
+
+impl AssetLoader for LevelPackLoader {
+    type Asset = LevelPack;
+    type Settings = ();
+    type Error = LevelLoadError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<LevelPack, LevelLoadError> {
+        let path = load_context.path().to_path_buf();
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(|source| LevelLoadError::Io { path: path.clone(), source })?;
+        let contents = String::from_utf8(bytes).map_err(|err| LevelLoadError::Parse {
+            path: path.clone(),
+            field: ".".to_string(),
+            message: err.to_string(),
+        })?;
+        let levels = if is_level_pack_file(&path) {
+            parse_level_pack(&path, &contents)?
+        } else {
+            vec![parse_level(&path, &contents)?]
+        };
+        Ok(LevelPack { levels })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        LEVEL_FILE_EXTENSIONS
+    }
+}
+
+/// Where the `LevelLibrary` gets its levels from: levels supplied by the app, followed by
+/// one `LevelPack` per level file (single-level files first, then packs, each in file
+/// name order) so that editing any of them on disk rebuilds the library.
+#[derive(Resource, Default)]
+pub struct LevelPackSources {
+    pub loose_levels: Vec<PuzzleSpec>,
+    pub packs: Vec<Handle<LevelPack>>,
+}
+
+impl LevelPackSources {
+    fn collect_levels(&self, packs: &Assets<LevelPack>) -> Vec<PuzzleSpec> {
+        let mut levels = self.loose_levels.clone();
+        for handle in &self.packs {
+            if let Some(pack) = packs.get(handle) {
+                levels.extend(pack.levels.iter().cloned());
+            }
+        }
+        levels
+    }
+
+    // Whether any file is still loading. Files that failed to load count as settled.
+    fn loading(&self, asset_server: &AssetServer) -> bool {
+        self.packs.iter().any(|handle| {
+            matches!(asset_server.get_load_state(handle), Some(LoadState::Loading | LoadState::NotLoaded))
+        })
+    }
+}
+
+/// Rebuilds the `LevelLibrary` whenever a level file finishes loading or changes on disk,
+/// waiting until no other file is still loading so the library never comes up short.
+/// If the authored level being played was edited, it is rebuilt through `setup_level_system`,
+/// carrying over the player's connections that still fit the new node list.
+pub fn level_pack_reload_system(
+    mut pack_events: EventReader<AssetEvent<LevelPack>>,
+    packs: Res<Assets<LevelPack>>,
+    asset_server: Res<AssetServer>,
+    sources: Res<LevelPackSources>,
+    mut rebuild_pending: Local<bool>,
+    mut level_library: ResMut<LevelLibrary>,
+    mut current_level: ResMut<CurrentLevel>,
+    puzzle_spec: Res<PuzzleSpec>,
+    player_attempt: Res<PlayerAttempt>,
+    mut level_reload: ResMut<LevelReload>,
+    game_state: Res<State<GameState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    for event in pack_events.read() {
+        match event {
+            AssetEvent::LoadedWithDependencies { .. } | AssetEvent::Modified { .. } | AssetEvent::Removed { .. } => {
+                *rebuild_pending = true;
+            }
+            _ => {}
+        }
+    }
+    if !*rebuild_pending || sources.loading(&asset_server) {
+        return;
+    }
+    *rebuild_pending = false;
+
+    level_library.levels = sources.collect_levels(&packs);
+    println!("Level library rebuilt: {} level(s)", level_library.levels.len());
+    // Endless levels are numbered from the end of the library, so changing its length
+    // mid-run would renumber the level being played; the new count applies from the next level
+    if current_level.is_endless() {
+        return;
+    }
+    current_level.total_levels = level_library.levels.len().max(1);
+
+    if *game_state.get() != GameState::Playing {
+        return;
+    }
+    let Some(new_spec) = level_library.levels.get(current_level.level_id) else {
+        return;
+    };
+    if *new_spec != *puzzle_spec {
+        println!("Level {} changed on disk, rebuilding.", current_level.level_id);
+        level_reload.preserved_connections = Some(player_attempt.board.drawn_connections().clone());
+        next_game_state.set(GameState::LoadingLevel);
+    }
+}
@@ -1,15 +1,23 @@
 use bevy::prelude::*; 
 use crate::game_state::GameState;
-use crate::resources::{CurrentLevel, PlayerAttempt, PuzzleSpec};
+use crate::resources::{CurrentLevel, EchoState, EndlessRun, GameTimer, HintStats, LevelFailReason, LevelSolutions, LevelLibrary, LevelReload, LevelSource, MistakeCounter, PlayerAttempt, PuzzleSpec, WrongConnectionPolicy};
+use crate::board::ConnectError;
+use crate::scoring::LevelResult;
 use crate::components::GameplayUI;
 
 pub mod node;
 pub mod connection;
 pub mod puzzle;
 pub mod echo;
+pub mod level_pack;
+pub mod obstacle;
+pub mod hint;
 
 use connection::DragState;
+use level_pack::{LevelPack, LevelPackLoader, LevelPackSources};
 
+/// The player dragged from `node1_id` to `node2_id`. On directed levels the connection
+/// runs in that direction.
 #[derive(Event, Debug)]
 pub struct ConnectionAttemptEvent {
     pub node1_id: usize,
@@ -19,6 +27,28 @@ pub struct ConnectionAttemptEvent {
 #[derive(Event, Debug)]
 pub struct PuzzleCompleteEvent;
 
+/// Written when a connection attempt breaks one of the board's rules, or is wrong under
+/// `WrongConnectionPolicy::Reject`. On no-crossing levels `reason` names the connection
+/// or node that was in the way.
+#[derive(Event, Debug)]
+pub struct ConnectionRejectedEvent {
+    pub node1_id: usize,
+    pub node2_id: usize,
+    pub reason: ConnectError,
+}
+
+/// Written when the player erases a drawn connection. Ids are in the order the board stores
+/// them: smallest first, or from -> to on directed levels.
+#[derive(Event, Debug)]
+pub struct ConnectionRemovedEvent {
+    pub node1_id: usize,
+    pub node2_id: usize,
+}
+
+/// The player asked for a hint, with `H` or the HUD button.
+#[derive(Event, Debug)]
+pub struct HintRequestEvent;
+
 pub struct GameplayPlugin;
 
 impl Plugin for GameplayPlugin {
@@ -26,22 +56,69 @@ impl Plugin for GameplayPlugin {
         app
             .add_event::<ConnectionAttemptEvent>()
             .add_event::<PuzzleCompleteEvent>()
+            .add_event::<ConnectionRemovedEvent>()
+            .add_event::<ConnectionRejectedEvent>()
+            .add_event::<HintRequestEvent>()
+            .add_event::<LevelResult>()
             .init_resource::<CurrentLevel>()
             .init_resource::<PlayerAttempt>()
             .init_resource::<PuzzleSpec>()
             .init_resource::<DragState>()
+            .init_resource::<LevelLibrary>()
+            .init_resource::<LevelSource>()
+            .init_resource::<EndlessRun>()
+            .init_resource::<LevelReload>()
+            .init_resource::<LevelPackSources>()
+            .init_resource::<MistakeCounter>()
+            .init_resource::<HintStats>()
+            .init_resource::<LevelSolutions>()
+            .init_resource::<GameTimer>()
+            .init_resource::<LevelFailReason>()
+            .init_resource::<LevelResult>()
+            .init_resource::<EchoState>()
+            .init_resource::<WrongConnectionPolicy>()
+            .init_asset::<LevelPack>()
+            .init_asset_loader::<LevelPackLoader>()
+            .add_systems(Startup, puzzle::load_level_library_system)
+            .add_systems(Update, level_pack::level_pack_reload_system)
             .add_systems(OnEnter(GameState::LoadingLevel), puzzle::setup_level_system)
             .add_systems(Update, 
                 (
                     node::node_interaction_system,
-                    echo::echo_visualization_system, 
                     connection::draw_connection_system,
-                    connection::check_connection_attempt_system,
+                    (
+                        connection::check_connection_attempt_system,
+                        connection::remove_connection_system,
+                        hint::hint_button_system,
+                        gameplay_keyboard_input_system,
+                        hint::hint_request_system,
+                        connection::sync_connection_entities_system,
+                        connection::flash_blocking_connection_system,
+                        echo::echo_propagation_system,
+                        echo::echo_visualization_system,
+                    ).chain(),
                     connection::persistent_connection_render_system, 
+                    connection::tick_connection_flash_system,
+                    connection::rejected_connection_flash_system,
+                    obstacle::obstacle_render_system,
+                    hint::hint_render_system,
+                    hint::update_hint_button_text_system,
                     puzzle::check_puzzle_completion_system,
-                    gameplay_keyboard_input_system,
+                    puzzle::level_timer_system,
+                    puzzle::move_limit_system,
+                    puzzle::update_moves_text_system,
+                    puzzle::update_degree_badges_system,
+                    puzzle::update_cable_budget_text_system,
                 ).run_if(in_state(GameState::Playing))
             )
+            .add_systems(Update, (
+                // Both writers of PuzzleCompleteEvent run first, so the result is recorded
+                // in the same frame, while the state is still Playing
+                puzzle::record_level_result_system
+                    .after(puzzle::check_puzzle_completion_system)
+                    .after(gameplay_keyboard_input_system),
+                handle_puzzle_complete_event,
+            ).chain())
             .add_systems(OnExit(GameState::Playing), cleanup_gameplay_entities);
     }
 }
@@ -50,8 +127,29 @@ fn gameplay_keyboard_input_system(
     keyboard_input: Res<ButtonInput<KeyCode>>,
     mut next_game_state: ResMut<NextState<GameState>>,
     current_level: Res<CurrentLevel>,
+    mut player_attempt: ResMut<PlayerAttempt>,
      mut puzzle_complete_event: EventWriter<PuzzleCompleteEvent>, 
+    mut hint_request_writer: EventWriter<HintRequestEvent>,
+    mut level_result: ResMut<LevelResult>,
 ) {
+    let ctrl_held = keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if ctrl_held && keyboard_input.just_pressed(KeyCode::KeyZ) {
+        match player_attempt.undo() {
+            Some(action) => println!("Undid {:?}", action),
+            None if player_attempt.history.can_undo() => println!("Cannot undo: the board no longer allows it."),
+            None => println!("Nothing to undo."),
+        }
+    }
+    if ctrl_held && keyboard_input.just_pressed(KeyCode::KeyY) {
+        match player_attempt.redo() {
+            Some(action) => println!("Redid {:?}", action),
+            None if player_attempt.history.can_redo() => println!("Cannot redo: the board no longer allows it."),
+            None => println!("Nothing to redo."),
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyH) {
+        hint_request_writer.write(HintRequestEvent);
+    }
     if keyboard_input.just_pressed(KeyCode::KeyM) {
         next_game_state.set(GameState::MainMenu);
     }
@@ -59,13 +157,33 @@ fn gameplay_keyboard_input_system(
         next_game_state.set(GameState::LoadingLevel);
     }
     if keyboard_input.just_pressed(KeyCode::KeyN) && current_level.level_id < current_level.total_levels -1 {
-         next_game_state.set(GameState::LevelComplete); 
+        // Skipping is not completing: the complete screen shows an unscored result
+        *level_result = LevelResult::skipped(current_level.level_id);
+        next_game_state.set(GameState::LevelComplete); 
     }
     if keyboard_input.just_pressed(KeyCode::Space) { 
         puzzle_complete_event.write(PuzzleCompleteEvent);
     }
 }
 
+// Moves a finished level on to GameState::LevelComplete
+fn handle_puzzle_complete_event(
+    mut puzzle_complete_reader: EventReader<PuzzleCompleteEvent>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    game_state: Res<bevy::state::state::State<GameState>>,
+) {
+    if *game_state.get() == GameState::LevelComplete {
+        return;
+    }
+    
+    for _ in puzzle_complete_reader.read() {
+        if *game_state.get() == GameState::Playing {
+            next_game_state.set(GameState::LevelComplete);
+            break;
+        }
+    }
+}
+
 fn cleanup_gameplay_entities(
     mut commands: Commands, 
     node_query: Query<Entity, With<crate::components::Node>>,
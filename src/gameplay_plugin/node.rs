@@ -1,12 +1,14 @@
 use bevy::prelude::*;
 use crate::components::{Node, ActivatedNode};
+use crate::resources::PlayerAttempt;
 
 pub fn node_interaction_system(
     mut commands: Commands,
     mouse_button_input: Res<ButtonInput<MouseButton>>,
     windows: Query<&Window>, // Changed to Query<&Window>
     camera_q: Query<(&Camera, &GlobalTransform)>,
-    node_query: Query<(Entity, &Transform, &Node), Without<ActivatedNode>>,
+    node_query: Query<(Entity, &Node), Without<ActivatedNode>>,
+    player_attempt: Res<PlayerAttempt>,
     mut selected_node_entity: Local<Option<Entity>>, 
 ) {
     // Get the primary window. If it doesn't exist, exit.
@@ -27,9 +29,9 @@ pub fn node_interaction_system(
             .map(|ray| ray.origin.truncate())
         {
             let mut clicked_on_node = false;
-            for (node_entity, node_transform, node_comp) in node_query.iter() {
-                let distance = world_position.distance(node_transform.translation.truncate());
-                if distance < 25.0 { // Node radius
+            let clicked_node_id = player_attempt.board.node_at(world_position);
+            for (node_entity, node_comp) in node_query.iter() {
+                if clicked_node_id == Some(node_comp.id) {
                     println!("Clicked node to activate: {}", node_comp.id);
                     
                     if let Some(prev_selected) = *selected_node_entity {
@@ -0,0 +1,46 @@
+use bevy::prelude::*;
+use crate::components::{GameplayUI, ObstacleShape};
+use crate::resources::Obstacle;
+
+const OBSTACLE_COLOR: Color = Color::srgb(0.6, 0.6, 0.65);
+
+// Rough centre of an obstacle, used as its entity's position
+fn obstacle_center(obstacle: &Obstacle) -> Vec2 {
+    match obstacle {
+        Obstacle::Rect { min, max } => (*min + *max) / 2.0,
+        Obstacle::Circle { center, .. } => *center,
+        Obstacle::Polyline { points } if !points.is_empty() => {
+            points.iter().copied().sum::<Vec2>() / points.len() as f32
+        }
+        Obstacle::Polyline { .. } => Vec2::ZERO,
+    }
+}
+
+/// Spawns one entity per obstacle in the level. Called from `setup_level_system`.
+pub fn spawn_obstacles(commands: &mut Commands, obstacles: &[Obstacle]) {
+    for (idx, obstacle) in obstacles.iter().enumerate() {
+        commands.spawn((
+            ObstacleShape(obstacle.clone()),
+            Transform::from_translation(obstacle_center(obstacle).extend(0.0)),
+            Name::new(format!("Obstacle_{}", idx)),
+            GameplayUI,
+        ));
+    }
+}
+
+pub fn obstacle_render_system(obstacle_query: Query<&ObstacleShape>, mut gizmos: Gizmos) {
+    for ObstacleShape(obstacle) in obstacle_query.iter() {
+        match obstacle {
+            Obstacle::Rect { min, max } => {
+                let rect = Rect::from_corners(*min, *max);
+                gizmos.rect_2d(rect.center(), rect.size(), OBSTACLE_COLOR);
+            }
+            Obstacle::Circle { center, radius } => {
+                gizmos.circle_2d(*center, *radius, OBSTACLE_COLOR);
+            }
+            Obstacle::Polyline { points } => {
+                gizmos.linestrip_2d(points.iter().copied(), OBSTACLE_COLOR);
+            }
+        }
+    }
+}
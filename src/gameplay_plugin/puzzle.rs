@@ -1,28 +1,115 @@
 use bevy::prelude::*;
-use crate::components::{Node, GameplayUI};
-use crate::resources::{CurrentLevel, PuzzleSpec, PlayerAttempt, GameFont};
+use bevy::asset::io::file::FileAssetReader;
+use crate::components::{CableBudgetText, DegreeBadge, LevelTimerText, MovesText, Node, GameplayUI};
+use crate::levels::{level_files_in_dir, level_pack_files_in_dir, load_levels_from_dir};
+use crate::board::PuzzleBoard;
+use crate::generator::generate_endless_puzzle;
+use crate::goals::GraphGoal;
+use crate::resources::{CurrentLevel, EndlessRun, NodeKind, Objective, PuzzleSpec, PlayerAttempt, LevelLibrary, LevelReload, LevelSource, MistakeCounter, EchoState, HintStats, GameTimer, LevelFailReason, LevelSolutions, format_clock};
 use crate::game_state::GameState;
+use crate::scoring::LevelResult;
+use super::level_pack::LevelPackSources;
+use super::hint::spawn_hint_button;
+use super::obstacle::spawn_obstacles;
 use super::PuzzleCompleteEvent; 
-// use std::collections::HashSet; // Removed as unused
-
-const MAX_LEVELS: usize = 2;
-fn get_level_spec(level_id: usize) -> PuzzleSpec {
-    match level_id {
-        0 => PuzzleSpec { 
-            node_positions: vec![
-                Vec2::new(-150.0, 0.0), Vec2::new(0.0, 100.0), Vec2::new(150.0, 0.0)
-            ],
-            correct_connections: [(0,1), (1,2)].iter().cloned().collect(),
-        },
-        1 => PuzzleSpec { 
-            node_positions: vec![
-                Vec2::new(-200.0, 100.0), Vec2::new(-200.0, -100.0),
-                Vec2::new(0.0, 0.0),
-                Vec2::new(200.0, 100.0), Vec2::new(200.0, -100.0),
-            ],
-            correct_connections: [(0,2), (1,2), (2,3), (2,4)].iter().cloned().collect(),
-        },
-        _ => get_level_spec(0), 
+
+/// Fills the `LevelLibrary` from the configured `LevelSource`. For a directory, single-level
+/// files are read right away so the first level can start at once, and every level file
+/// and pack also starts loading through the asset server, which keeps them hot reloadable.
+/// Malformed files are reported with the offending field and skipped.
+pub fn load_level_library_system(
+    level_source: Res<LevelSource>,
+    mut level_library: ResMut<LevelLibrary>,
+    mut sources: ResMut<LevelPackSources>,
+    asset_server: Res<AssetServer>,
+) {
+    let dir = match &*level_source {
+        LevelSource::Inline(levels) => {
+            println!("Using {} level(s) supplied by the app", levels.len());
+            level_library.levels = levels.clone();
+            sources.loose_levels = levels.clone();
+            return;
+        }
+        LevelSource::Directory(dir) => dir,
+    };
+
+    let levels_dir = FileAssetReader::get_base_path().join("assets").join(dir);
+    let (levels, errors) = load_levels_from_dir(&levels_dir);
+    for err in &errors {
+        eprintln!("Skipping level: {}", err);
+    }
+    println!("Loaded {} level(s) from {}", levels.len(), levels_dir.display());
+    level_library.levels = levels;
+
+    // These arrive asynchronously; level_pack_reload_system rebuilds the library from them
+    // once they have all loaded, adding the packs after the single-level files
+    let level_files = level_files_in_dir(&levels_dir).unwrap_or_default();
+    let pack_files = level_pack_files_in_dir(&levels_dir).unwrap_or_default();
+    for file in level_files.into_iter().chain(pack_files) {
+        if let Some(file_name) = file.file_name().and_then(|name| name.to_str()) {
+            println!("Loading level file {}", file_name);
+            sources.packs.push(asset_server.load(format!("{}/{}", dir, file_name)));
+        }
+    }
+}
+
+fn level_hud_label(current_level: &CurrentLevel, puzzle_spec: &PuzzleSpec) -> String {
+    let mut label = if current_level.is_endless() {
+        format!("Endless: {}", current_level.level_id - current_level.total_levels + 1)
+    } else {
+        format!("Level: {}/{}", current_level.level_id + 1, current_level.total_levels)
+    };
+    if !puzzle_spec.title.is_empty() {
+        label.push_str(&format!(" - {}", puzzle_spec.title));
+    }
+    if let Some(par) = puzzle_spec.par {
+        label.push_str(&format!(" (par {})", par));
+    }
+    if puzzle_spec.directed {
+        label.push_str(" [one-way]");
+    }
+    if puzzle_spec.no_crossing {
+        label.push_str(" [no crossing]");
+    }
+    label
+}
+
+fn node_kind_color(kind: NodeKind) -> Color {
+    match kind {
+        NodeKind::Relay => Color::srgb(0.2, 0.2, 0.8),
+        NodeKind::Source => Color::srgb(0.2, 0.65, 0.3),
+        NodeKind::Sink => Color::srgb(0.6, 0.25, 0.7),
+        NodeKind::Splitter(_) => Color::srgb(0.85, 0.5, 0.1),
+        NodeKind::Blocker => Color::srgb(0.3, 0.3, 0.3),
+    }
+}
+
+// Drawn on top of the node sprite so kinds can be told apart without relying on color
+fn node_kind_label(kind: NodeKind) -> Option<String> {
+    match kind {
+        NodeKind::Relay => None,
+        NodeKind::Source => Some("OUT".to_string()),
+        NodeKind::Sink => Some("IN".to_string()),
+        NodeKind::Splitter(n) => Some(format!("x{}", n)),
+        NodeKind::Blocker => Some("X".to_string()),
+    }
+}
+
+// Text shown above the nodes an objective refers to: for echo objectives the source, then
+// either the position of each target in the arrival order or the tick it must be reached at
+fn objective_node_labels(objective: &Objective) -> Vec<(usize, String)> {
+    match objective {
+        Objective::MatchConnections | Objective::MatchDegrees => Vec::new(),
+        Objective::Goal(GraphGoal::HamiltonianPath { from, to }) => {
+            vec![(*from, "Start".to_string()), (*to, "End".to_string())]
+        }
+        Objective::Goal(_) => Vec::new(),
+        Objective::EchoOrder { source, order } => std::iter::once((*source, "Source".to_string()))
+            .chain(order.iter().enumerate().map(|(idx, &node)| (node, format!("#{}", idx + 1))))
+            .collect(),
+        Objective::EchoTicks { source, arrivals } => std::iter::once((*source, "Source".to_string()))
+            .chain(arrivals.iter().map(|&(node, tick)| (node, format!("t={}", tick))))
+            .collect(),
     }
 }
 
@@ -32,36 +119,121 @@ pub fn setup_level_system(
     mut puzzle_spec: ResMut<PuzzleSpec>,
     mut player_attempt: ResMut<PlayerAttempt>,
     mut next_game_state: ResMut<NextState<GameState>>,
-    game_font: Res<GameFont>, 
+    level_library: Res<LevelLibrary>,
+    endless_run: Res<EndlessRun>,
+    mut level_reload: ResMut<LevelReload>,
+    mut mistake_counter: ResMut<MistakeCounter>,
+    mut hint_stats: ResMut<HintStats>,
+    mut level_solutions: ResMut<LevelSolutions>,
+    mut game_timer: ResMut<GameTimer>,
+    mut echo_state: ResMut<EchoState>,
 ) {
-    current_level.total_levels = MAX_LEVELS;
+    // With no usable level files, fall back to the built-in default puzzle
+    current_level.total_levels = level_library.levels.len().max(1);
     
-    if current_level.level_id >= MAX_LEVELS {
-        current_level.level_id = 0;
+    if current_level.is_endless() {
+        let depth = current_level.level_id - current_level.total_levels;
+        *puzzle_spec = match generate_endless_puzzle(endless_run.base_seed, depth) {
+            Ok(spec) => spec,
+            Err(err) => {
+                eprintln!("Could not generate endless level {} (base seed {}): {}", depth, endless_run.base_seed, err);
+                PuzzleSpec::default()
+            }
+        };
+    } else {
+        *puzzle_spec = level_library
+            .levels
+            .get(current_level.level_id)
+            .cloned()
+            .unwrap_or_default();
     }
-    
-    *puzzle_spec = get_level_spec(current_level.level_id);
-    player_attempt.drawn_connections.clear(); 
+    // A reload carries on the same attempt, so it keeps its move count
+    let moves = if level_reload.preserved_connections.is_some() { player_attempt.moves } else { 0 };
+    *player_attempt = PlayerAttempt::new(PuzzleBoard::new(&puzzle_spec));
+    player_attempt.moves = moves;
+    *echo_state = EchoState::default();
+    *level_solutions = LevelSolutions::for_spec(&puzzle_spec);
 
-    commands.spawn((Camera2d::default(), GameplayUI));
+    commands.spawn((Camera2d, GameplayUI));
 
     for (idx, pos) in puzzle_spec.node_positions.iter().enumerate() {
-        let node_color = Color::srgb(0.2, 0.2, 0.8); // Corrected Color
-        commands.spawn((
+        let kind = player_attempt.board.node_kind(idx);
+        let node_color = node_kind_color(kind);
+        let mut node_commands = commands.spawn((
             Sprite {
                 color: node_color,
                 custom_size: Some(Vec2::new(50.0, 50.0)),
                 ..default()
             },
             Transform::from_translation(pos.extend(0.0)),
-            Node { id: idx, original_color: node_color },
+            Node { id: idx, original_color: node_color, kind },
             Name::new(format!("Node_{}", idx)),
             GameplayUI, 
         ));
+        // Badge in the node's top-right corner counting down the connections it still needs
+        if let Some(remaining) = player_attempt.board.remaining_degree(idx) {
+            node_commands.with_children(|parent| {
+                parent.spawn((
+                    Text2d::new(remaining.to_string()),
+                    TextFont {
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextColor(degree_badge_color(remaining)),
+                    Transform::from_translation(Vec3::new(22.0, 22.0, 1.0)),
+                    DegreeBadge { node_id: idx },
+                ));
+            });
+        }
+        if let Some(label) = node_kind_label(kind) {
+            commands.spawn((
+                Text2d::new(label),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Transform::from_translation(pos.extend(1.0)),
+                GameplayUI,
+            ));
+        }
+    }
+
+    spawn_obstacles(&mut commands, &puzzle_spec.obstacles);
+
+    for (node, label) in objective_node_labels(&puzzle_spec.objective) {
+        if let Some(pos) = puzzle_spec.node_positions.get(node) {
+            commands.spawn((
+                Text2d::new(label),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.3, 0.8, 0.9)),
+                Transform::from_translation((*pos + Vec2::new(0.0, 40.0)).extend(1.0)),
+                GameplayUI,
+            ));
+        }
     }
+
+    // After a hot reload, keep every connection the new board still accepts. The
+    // Connection entities are spawned by sync_connection_entities_system once Playing.
+    if let Some(preserved) = level_reload.preserved_connections.take() {
+        for (id1, id2) in preserved {
+            let _ = player_attempt.board.restore_connection(id1, id2);
+        }
+        println!("Restored connections after reload: {:?}", player_attempt.board.drawn_connections());
+    } else {
+        // A reload continues the same attempt; anything else starts the level afresh
+        mistake_counter.mistakes = 0;
+        hint_stats.used = 0;
+        game_timer.reset();
+    }
+
+    spawn_hint_button(&mut commands, hint_stats.remaining());
     
      commands.spawn((
-        Text2d::new(format!("Level: {}/{}", current_level.level_id + 1, current_level.total_levels)),
+        Text2d::new(level_hud_label(&current_level, &puzzle_spec)),
         TextFont {
             font_size: 30.0,
             ..default()
@@ -71,25 +243,224 @@ pub fn setup_level_system(
         GameplayUI
     ));
 
+    if let Objective::Goal(goal) = &puzzle_spec.objective {
+        commands.spawn((
+            Text2d::new(goal.description()),
+            TextFont {
+                font_size: 22.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.3, 0.8, 0.9)),
+            Transform::from_translation(Vec3::new(0.0, -270.0, 1.0)),
+            GameplayUI,
+        ));
+    }
+
+    commands.spawn((
+        Text2d::new(level_timer_label(&game_timer, puzzle_spec.time_limit)),
+        TextFont {
+            font_size: 24.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Transform::from_translation(Vec3::new(300.0, 230.0, 1.0)),
+        LevelTimerText,
+        GameplayUI,
+    ));
+
+    commands.spawn((
+        Text2d::new(moves_label(player_attempt.moves, puzzle_spec.max_moves)),
+        TextFont {
+            font_size: 24.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Transform::from_translation(Vec3::new(300.0, 200.0, 1.0)),
+        MovesText,
+        GameplayUI,
+    ));
+
+    if let Some(budget) = player_attempt.board.cable_budget() {
+        let remaining = player_attempt.board.remaining_cable().unwrap_or(budget);
+        commands.spawn((
+            Text2d::new(cable_budget_label(remaining, budget)),
+            TextFont {
+                font_size: 24.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Transform::from_translation(Vec3::new(-380.0, 235.0, 1.0)),
+            CableBudgetText,
+            GameplayUI,
+        ));
+    }
+
     println!("Setting up Level: {}", current_level.level_id);
     next_game_state.set(GameState::Playing);
 }
 
-pub fn check_puzzle_completion_system(
+fn degree_badge_color(remaining: usize) -> Color {
+    if remaining == 0 { Color::srgb(0.4, 1.0, 0.4) } else { Color::srgb(1.0, 0.9, 0.3) }
+}
+
+pub fn update_degree_badges_system(
+    player_attempt: Res<PlayerAttempt>,
+    mut badge_query: Query<(&DegreeBadge, &mut Text2d, &mut TextColor)>,
+) {
+    if !player_attempt.is_changed() {
+        return;
+    }
+    for (badge, mut text, mut color) in badge_query.iter_mut() {
+        if let Some(remaining) = player_attempt.board.remaining_degree(badge.node_id) {
+            text.0 = remaining.to_string();
+            color.0 = degree_badge_color(remaining);
+        }
+    }
+}
+
+fn level_timer_label(game_timer: &GameTimer, time_limit: Option<f32>) -> String {
+    match time_limit {
+        Some(limit) => format!("Time left: {}", format_clock(game_timer.remaining_secs(limit).ceil())),
+        None => format!("Time: {}", format_clock(game_timer.elapsed.as_secs_f32())),
+    }
+}
+
+/// Counts time spent on the level, refreshes the HUD clock once a second, and fails the
+/// level when a time limit runs out before it is solved.
+pub fn level_timer_system(
+    time: Res<Time>,
+    mut game_timer: ResMut<GameTimer>,
+    puzzle_spec: Res<PuzzleSpec>,
+    player_attempt: Res<PlayerAttempt>,
+    mut text_query: Query<&mut Text2d, With<LevelTimerText>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut fail_reason: ResMut<LevelFailReason>,
+) {
+    // A solved level is on its way to LevelComplete; stop the clock where it is
+    if player_attempt.board.is_solved() {
+        return;
+    }
+    game_timer.elapsed += time.delta();
+    game_timer.timer.tick(time.delta());
+
+    if let Some(limit) = puzzle_spec.time_limit {
+        if game_timer.remaining_secs(limit) == 0.0 {
+            println!("Time's up after {:.1}s", game_timer.elapsed.as_secs_f32());
+            *fail_reason = LevelFailReason::TimeUp;
+            next_game_state.set(GameState::LevelFailed);
+        }
+    }
+    if game_timer.timer.just_finished() {
+        for mut text in text_query.iter_mut() {
+            text.0 = level_timer_label(&game_timer, puzzle_spec.time_limit);
+        }
+    }
+}
+
+fn moves_label(moves: usize, max_moves: Option<usize>) -> String {
+    match max_moves {
+        Some(max) => format!("Moves: {}/{}", moves, max),
+        None => format!("Moves: {}", moves),
+    }
+}
+
+/// Fails a move-limit level once the player has made more moves than it allows,
+/// unless the last one solved it.
+pub fn move_limit_system(
+    player_attempt: Res<PlayerAttempt>,
     puzzle_spec: Res<PuzzleSpec>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut fail_reason: ResMut<LevelFailReason>,
+) {
+    if !player_attempt.is_changed() || !player_attempt.out_of_moves(puzzle_spec.max_moves) {
+        return;
+    }
+    if !player_attempt.board.is_solved() {
+        println!("Out of moves after {}", player_attempt.moves);
+        *fail_reason = LevelFailReason::OutOfMoves;
+        next_game_state.set(GameState::LevelFailed);
+    }
+}
+
+pub fn update_moves_text_system(
+    player_attempt: Res<PlayerAttempt>,
+    puzzle_spec: Res<PuzzleSpec>,
+    mut text_query: Query<(&mut Text2d, &mut TextColor), With<MovesText>>,
+) {
+    if !player_attempt.is_changed() {
+        return;
+    }
+    let over_par = puzzle_spec.par.is_some_and(|par| player_attempt.moves > par);
+    for (mut text, mut color) in text_query.iter_mut() {
+        text.0 = moves_label(player_attempt.moves, puzzle_spec.max_moves);
+        color.0 = if over_par { Color::srgb(1.0, 0.7, 0.4) } else { Color::WHITE };
+    }
+}
+
+fn cable_budget_label(remaining: f32, budget: f32) -> String {
+    format!("Cable: {:.0}/{:.0}", remaining, budget)
+}
+
+pub fn update_cable_budget_text_system(
+    player_attempt: Res<PlayerAttempt>,
+    mut text_query: Query<&mut Text2d, With<CableBudgetText>>,
+) {
+    if !player_attempt.is_changed() {
+        return;
+    }
+    let (Some(budget), Some(remaining)) = (player_attempt.board.cable_budget(), player_attempt.board.remaining_cable()) else {
+        return;
+    };
+    for mut text in text_query.iter_mut() {
+        text.0 = cable_budget_label(remaining, budget);
+    }
+}
+
+/// Scores the level when it is completed, keeping the result for the level-complete
+/// screen and writing it as an event for anything that tracks progress.
+pub fn record_level_result_system(
+    mut puzzle_complete_reader: EventReader<PuzzleCompleteEvent>,
+    game_state: Res<bevy::state::state::State<GameState>>,
+    current_level: Res<CurrentLevel>,
+    puzzle_spec: Res<PuzzleSpec>,
+    game_timer: Res<GameTimer>,
+    player_attempt: Res<PlayerAttempt>,
+    mistake_counter: Res<MistakeCounter>,
+    hint_stats: Res<HintStats>,
+    mut level_result: ResMut<LevelResult>,
+    mut level_result_writer: EventWriter<LevelResult>,
+) {
+    // Like handle_puzzle_complete_event, only the first completion while playing counts
+    if puzzle_complete_reader.read().count() == 0 || *game_state.get() != GameState::Playing {
+        return;
+    }
+    *level_result = LevelResult::new(
+        &puzzle_spec,
+        current_level.level_id,
+        game_timer.elapsed,
+        player_attempt.moves,
+        mistake_counter.mistakes,
+        hint_stats.used,
+    );
+    println!("Level result: {:?}", *level_result);
+    level_result_writer.write(level_result.clone());
+}
+
+pub fn check_puzzle_completion_system(
     player_attempt: Res<PlayerAttempt>,
     mut puzzle_complete_event: EventWriter<PuzzleCompleteEvent>,
     mut already_fired_event: Local<bool>, 
     game_state: Res<bevy::state::state::State<GameState>>,
 ) {
-    if *game_state.get() != GameState::Playing { 
+    // State changes whenever a level is (re)entered, so each level gets a fresh guard
+    if game_state.is_changed() || *game_state.get() != GameState::Playing { 
         *already_fired_event = false; 
+    }
+    if *game_state.get() != GameState::Playing { 
         return;
     }
 
-    if !*already_fired_event && 
-       player_attempt.drawn_connections.len() == puzzle_spec.correct_connections.len() &&
-       player_attempt.drawn_connections.is_subset(&puzzle_spec.correct_connections) {
+    if !*already_fired_event && player_attempt.board.is_solved() {
         println!("Puzzle Complete!");
         puzzle_complete_event.write(PuzzleCompleteEvent);
         *already_fired_event = true;
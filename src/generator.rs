@@ -0,0 +1,195 @@
+//! Procedural puzzle generation.
+//!
+//! `generate_puzzle` turns a seed and a set of `GeneratorSettings` into a `PuzzleSpec`.
+//! The same seed and settings always give the same puzzle, so a generated level can be
+//! shared or replayed by its seed alone.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use bevy::math::{Rect, Vec2};
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::board::normalize_pair;
+use crate::components::NODE_HIT_RADIUS;
+use crate::difficulty::difficulty;
+use crate::resources::{Objective, PuzzleSpec};
+use crate::{WINDOW_HEIGHT, WINDOW_WIDTH};
+
+// How many positions are tried for a node before the settings are declared impossible
+const PLACEMENT_ATTEMPTS: usize = 500;
+
+/// Endless levels are generated this many at a time and played easiest first.
+pub const ENDLESS_BATCH: usize = 5;
+
+#[derive(Debug, Clone)]
+pub struct GeneratorSettings {
+    pub node_count: usize,
+    /// Number of edges in the solution. Clamped so the graph is connected
+    /// (at least `node_count - 1`) and has no repeated pairs.
+    pub edge_count: usize,
+    /// Minimum distance between any two node centres.
+    pub min_spacing: f32,
+    /// Region node centres are placed in, in world units around the camera.
+    pub play_area: Rect,
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        GeneratorSettings {
+            node_count: 5,
+            edge_count: 5,
+            min_spacing: 120.0,
+            play_area: default_play_area(),
+        }
+    }
+}
+
+impl GeneratorSettings {
+    /// Settings for the `depth`-th generated level of an endless run; puzzles grow
+    /// by roughly one node every other level until the window is full.
+    pub fn endless(depth: usize) -> Self {
+        let node_count = (4 + depth / 2).min(12);
+        GeneratorSettings {
+            node_count,
+            edge_count: node_count - 1 + (depth / 3).min(node_count),
+            min_spacing: 110.0,
+            play_area: default_play_area(),
+        }
+    }
+}
+
+// The 800x600 window minus half a node sprite on every side and the HUD strip at the top
+fn default_play_area() -> Rect {
+    let margin = NODE_HIT_RADIUS * 2.0;
+    Rect::new(
+        -WINDOW_WIDTH / 2.0 + margin,
+        -WINDOW_HEIGHT / 2.0 + margin,
+        WINDOW_WIDTH / 2.0 - margin,
+        WINDOW_HEIGHT / 2.0 - margin * 2.0,
+    )
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeneratorError {
+    /// Fewer than two nodes were requested.
+    TooFewNodes(usize),
+    /// The nodes could not be spread out with the requested spacing inside the play area.
+    NoRoom { placed: usize, requested: usize },
+}
+
+impl fmt::Display for GeneratorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeneratorError::TooFewNodes(count) => {
+                write!(f, "a generated puzzle needs at least 2 nodes, got {}", count)
+            }
+            GeneratorError::NoRoom { placed, requested } => write!(
+                f,
+                "only {} of {} nodes fit in the play area with the requested spacing",
+                placed, requested
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GeneratorError {}
+
+/// Builds a puzzle from `seed`. The solution is always a connected graph: a random
+/// spanning tree plus extra edges up to `settings.edge_count`.
+pub fn generate_puzzle(seed: u64, settings: &GeneratorSettings) -> Result<PuzzleSpec, GeneratorError> {
+    if settings.node_count < 2 {
+        return Err(GeneratorError::TooFewNodes(settings.node_count));
+    }
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    let node_positions = place_nodes(&mut rng, settings)?;
+    let node_count = node_positions.len();
+
+    let max_edges = node_count * (node_count - 1) / 2;
+    let edge_count = settings.edge_count.clamp(node_count - 1, max_edges);
+
+    // Spanning tree: visit nodes in a random order and hook each one onto a node
+    // that is already part of the tree.
+    let mut order: Vec<usize> = (0..node_count).collect();
+    order.shuffle(&mut rng);
+    let mut correct_connections: HashSet<(usize, usize)> = HashSet::new();
+    for i in 1..node_count {
+        let parent = order[rng.gen_range(0..i)];
+        correct_connections.insert(normalize_pair(order[i], parent));
+    }
+
+    // Extra edges, picked from the remaining pairs in a seed-determined order
+    let mut remaining: Vec<(usize, usize)> = (0..node_count)
+        .flat_map(|a| ((a + 1)..node_count).map(move |b| (a, b)))
+        .filter(|pair| !correct_connections.contains(pair))
+        .collect();
+    remaining.shuffle(&mut rng);
+    correct_connections.extend(remaining.into_iter().take(edge_count - (node_count - 1)));
+
+    Ok(PuzzleSpec {
+        title: format!("Generated #{}", seed),
+        par: Some(edge_count),
+        max_moves: None,
+        star_thresholds: None,
+        node_positions,
+        correct_connections,
+        objective: Objective::default(),
+        directed: false,
+        node_kinds: Vec::new(),
+        required_degrees: Vec::new(),
+        no_crossing: false,
+        obstacles: Vec::new(),
+        cable_budget: None,
+        time_limit: None,
+    })
+}
+
+/// The `depth`-th level of an endless run started from `base_seed`. Each level has its own
+/// seed (`base_seed + depth`) and `GeneratorSettings::endless(depth)`; the levels of each
+/// batch of `ENDLESS_BATCH` are then reordered by `difficulty`, so a run ramps up smoothly
+/// even when one seed happens to give a harder puzzle than the next.
+pub fn generate_endless_puzzle(base_seed: u64, depth: usize) -> Result<PuzzleSpec, GeneratorError> {
+    let batch_start = depth - depth % ENDLESS_BATCH;
+    let mut batch = (batch_start..batch_start + ENDLESS_BATCH)
+        .map(|batch_depth| {
+            let seed = base_seed.wrapping_add(batch_depth as u64);
+            let spec = generate_puzzle(seed, &GeneratorSettings::endless(batch_depth))?;
+            Ok((difficulty(&spec), spec))
+        })
+        .collect::<Result<Vec<(u32, PuzzleSpec)>, GeneratorError>>()?;
+    // Stable, so equally rated levels keep their seed order
+    batch.sort_by_key(|(score, _)| *score);
+    Ok(batch.swap_remove(depth - batch_start).1)
+}
+
+fn place_nodes(rng: &mut ChaCha8Rng, settings: &GeneratorSettings) -> Result<Vec<Vec2>, GeneratorError> {
+    let area = settings.play_area;
+    let mut positions: Vec<Vec2> = Vec::with_capacity(settings.node_count);
+
+    while positions.len() < settings.node_count {
+        let mut placed = false;
+        for _ in 0..PLACEMENT_ATTEMPTS {
+            // Snap to whole units so level files written from generated puzzles stay readable
+            let candidate = Vec2::new(
+                rng.gen_range(area.min.x..=area.max.x).round(),
+                rng.gen_range(area.min.y..=area.max.y).round(),
+            );
+            if positions.iter().all(|p| p.distance(candidate) >= settings.min_spacing) {
+                positions.push(candidate);
+                placed = true;
+                break;
+            }
+        }
+        if !placed {
+            return Err(GeneratorError::NoRoom {
+                placed: positions.len(),
+                requested: settings.node_count,
+            });
+        }
+    }
+
+    Ok(positions)
+}
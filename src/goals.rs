@@ -0,0 +1,129 @@
+//! Graph-shaped win conditions, checked against a plain set of edges.
+//!
+//! Each goal accepts every network with the right shape rather than one particular set of
+//! connections, so a level using one can be solved in many ways. Edges are node id pairs,
+//! each pair at most once, and are treated as undirected whichever way round they are stored.
+
+use std::collections::HashSet;
+
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub enum GraphGoal {
+    /// Every node can be reached from every other.
+    AllConnected,
+    /// Every node connected, with no cycles.
+    SpanningTree,
+    /// One path running through every node exactly once, from `from` to `to`.
+    HamiltonianPath { from: usize, to: usize },
+    /// One loop running through every node exactly once.
+    SingleCycle,
+    /// No node has more than this many connections.
+    MaxDegree(usize),
+}
+
+impl GraphGoal {
+    pub fn is_met(&self, node_count: usize, edges: &HashSet<(usize, usize)>) -> bool {
+        match self {
+            GraphGoal::AllConnected => all_connected(node_count, edges),
+            GraphGoal::SpanningTree => is_spanning_tree(node_count, edges),
+            GraphGoal::HamiltonianPath { from, to } => is_hamiltonian_path(node_count, edges, *from, *to),
+            GraphGoal::SingleCycle => is_single_cycle(node_count, edges),
+            GraphGoal::MaxDegree(limit) => max_degree(node_count, edges) <= *limit,
+        }
+    }
+
+    /// Node ids the goal refers to.
+    pub fn node_ids(&self) -> Vec<usize> {
+        match self {
+            GraphGoal::HamiltonianPath { from, to } => vec![*from, *to],
+            _ => Vec::new(),
+        }
+    }
+
+    /// One line telling the player what to build.
+    pub fn description(&self) -> String {
+        match self {
+            GraphGoal::AllConnected => "Connect every node".to_string(),
+            GraphGoal::SpanningTree => "Connect every node without forming a loop".to_string(),
+            GraphGoal::HamiltonianPath { .. } => "Make one path through every node, from Start to End".to_string(),
+            GraphGoal::SingleCycle => "Make one loop through every node".to_string(),
+            GraphGoal::MaxDegree(limit) => format!("No node may have more than {} connection(s)", limit),
+        }
+    }
+}
+
+// Connections per node; edges naming a node outside the board are ignored
+fn degrees(node_count: usize, edges: &HashSet<(usize, usize)>) -> Vec<usize> {
+    let mut degrees = vec![0; node_count];
+    for &(a, b) in edges {
+        if a < node_count && b < node_count {
+            degrees[a] += 1;
+            degrees[b] += 1;
+        }
+    }
+    degrees
+}
+
+/// Whether every node can reach every other. A board with no nodes counts as connected.
+pub fn all_connected(node_count: usize, edges: &HashSet<(usize, usize)>) -> bool {
+    if node_count == 0 {
+        return true;
+    }
+    let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); node_count];
+    for &(a, b) in edges {
+        if a < node_count && b < node_count {
+            adjacency[a].push(b);
+            adjacency[b].push(a);
+        }
+    }
+    let mut visited = vec![false; node_count];
+    visited[0] = true;
+    let mut stack = vec![0];
+    while let Some(node) = stack.pop() {
+        for &next in &adjacency[node] {
+            if !visited[next] {
+                visited[next] = true;
+                stack.push(next);
+            }
+        }
+    }
+    visited.into_iter().all(|seen| seen)
+}
+
+/// Whether the edges connect every node with no cycles: connected, with one edge fewer
+/// than there are nodes.
+pub fn is_spanning_tree(node_count: usize, edges: &HashSet<(usize, usize)>) -> bool {
+    node_count > 0 && edges.len() == node_count - 1 && all_connected(node_count, edges)
+}
+
+/// Whether the edges form one path visiting every node exactly once, with `from` and
+/// `to` at its ends. Either end may be where the path starts.
+pub fn is_hamiltonian_path(node_count: usize, edges: &HashSet<(usize, usize)>, from: usize, to: usize) -> bool {
+    if from >= node_count || to >= node_count {
+        return false;
+    }
+    if node_count == 1 {
+        return edges.is_empty();
+    }
+    let degrees = degrees(node_count, edges);
+    // A spanning tree whose only leaves are the two ends is exactly such a path
+    from != to
+        && is_spanning_tree(node_count, edges)
+        && degrees.iter().enumerate().all(|(node, &degree)| {
+            degree == if node == from || node == to { 1 } else { 2 }
+        })
+}
+
+/// Whether the edges form one loop through every node. Needs at least three nodes.
+pub fn is_single_cycle(node_count: usize, edges: &HashSet<(usize, usize)>) -> bool {
+    node_count >= 3
+        && edges.len() == node_count
+        && degrees(node_count, edges).into_iter().all(|degree| degree == 2)
+        && all_connected(node_count, edges)
+}
+
+/// The most connections any one node has.
+pub fn max_degree(node_count: usize, edges: &HashSet<(usize, usize)>) -> usize {
+    degrees(node_count, edges).into_iter().max().unwrap_or(0)
+}
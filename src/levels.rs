@@ -0,0 +1,294 @@
+//! Loading of level files from `assets/levels/`.
+//!
+//! Each file describes one `PuzzleSpec`, written either as RON (`.ron`) or JSON (`.json`).
+//! Levels are ordered by file name, so `level_01.ron` is played before `level_02.ron`.
+//!
+//! Level packs (`.levelpack.ron` / `.levelpack.json`) hold an ordered list of levels under a
+//! single `levels` field. Both kinds of file are also loaded through the asset server so they
+//! can be hot reloaded.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::board::normalize_pair;
+use crate::resources::{Objective, PuzzleSpec};
+
+/// Directory, relative to the asset root, that holds the level files.
+pub const LEVELS_DIR: &str = "levels";
+
+/// File name suffixes that mark a level pack rather than a single level.
+pub const LEVEL_PACK_EXTENSIONS: &[&str] = &["levelpack.ron", "levelpack.json"];
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LevelPackFile {
+    levels: Vec<PuzzleSpec>,
+}
+
+// Only the connection list, kept in file order so tools can spot repeated pairs that
+// the HashSet in PuzzleSpec would silently merge.
+#[derive(Deserialize)]
+struct RawLevel {
+    #[serde(default)]
+    correct_connections: Vec<(usize, usize)>,
+}
+
+#[derive(Deserialize)]
+struct RawLevelPack {
+    levels: Vec<RawLevel>,
+}
+
+#[derive(Debug)]
+pub enum LevelLoadError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The file could not be deserialized. `field` is the path to the offending value,
+    /// e.g. `node_positions[2]`, or `.` when the problem is at the top level.
+    Parse {
+        path: PathBuf,
+        field: String,
+        message: String,
+    },
+    /// The file deserialized but describes an impossible puzzle.
+    Invalid {
+        path: PathBuf,
+        field: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for LevelLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelLoadError::Io { path, source } => {
+                write!(f, "{}: could not read file: {}", path.display(), source)
+            }
+            LevelLoadError::Parse { path, field, message } => {
+                write!(f, "{}: field `{}`: {}", path.display(), field, message)
+            }
+            LevelLoadError::Invalid { path, field, message } => {
+                write!(f, "{}: field `{}`: {}", path.display(), field, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LevelLoadError {}
+
+fn file_name(path: &Path) -> &str {
+    path.file_name().and_then(|name| name.to_str()).unwrap_or_default()
+}
+
+pub fn is_level_pack_file(path: &Path) -> bool {
+    let name = file_name(path);
+    LEVEL_PACK_EXTENSIONS
+        .iter()
+        .any(|ext| name.ends_with(&format!(".{}", ext)))
+}
+
+fn is_level_file(path: &Path) -> bool {
+    !is_level_pack_file(path)
+        && matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("ron") | Some("json")
+        )
+}
+
+// Deserializes RON or JSON depending on the extension of `path`, reporting the path to
+// the offending field on failure.
+fn deserialize_file<T: DeserializeOwned>(path: &Path, contents: &str) -> Result<T, LevelLoadError> {
+    let parse_error = |field: String, message: String| LevelLoadError::Parse {
+        path: path.to_path_buf(),
+        field,
+        message,
+    };
+
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => {
+            let mut deserializer = serde_json::Deserializer::from_str(contents);
+            serde_path_to_error::deserialize(&mut deserializer)
+                .map_err(|err| parse_error(err.path().to_string(), err.inner().to_string()))
+        }
+        _ => {
+            let mut deserializer = ron::de::Deserializer::from_str(contents)
+                .map_err(|err| parse_error(".".to_string(), err.to_string()))?;
+            let value = serde_path_to_error::deserialize(&mut deserializer).map_err(|err| {
+                let field = err.path().to_string();
+                let spanned = deserializer.span_error(err.into_inner());
+                parse_error(field, spanned.to_string())
+            })?;
+            deserializer
+                .end()
+                .map_err(|err| parse_error(".".to_string(), deserializer.span_error(err).to_string()))?;
+            Ok(value)
+        }
+    }
+}
+
+/// Parses a single level file. The format is picked from the extension of `path`.
+pub fn parse_level(path: &Path, contents: &str) -> Result<PuzzleSpec, LevelLoadError> {
+    let spec = deserialize_file(path, contents)?;
+    validate_level(path, "", spec)
+}
+
+/// Parses a level pack. Errors name the level by index, e.g. `levels[1].node_positions`.
+pub fn parse_level_pack(path: &Path, contents: &str) -> Result<Vec<PuzzleSpec>, LevelLoadError> {
+    let pack: LevelPackFile = deserialize_file(path, contents)?;
+    pack.levels
+        .into_iter()
+        .enumerate()
+        .map(|(idx, spec)| validate_level(path, &format!("levels[{}].", idx), spec))
+        .collect()
+}
+
+/// Returns the connection list of every level in a level file or pack exactly as written,
+/// duplicates included.
+pub fn raw_connection_lists(path: &Path, contents: &str) -> Result<Vec<Vec<(usize, usize)>>, LevelLoadError> {
+    if is_level_pack_file(path) {
+        let pack: RawLevelPack = deserialize_file(path, contents)?;
+        Ok(pack.levels.into_iter().map(|level| level.correct_connections).collect())
+    } else {
+        let level: RawLevel = deserialize_file(path, contents)?;
+        Ok(vec![level.correct_connections])
+    }
+}
+
+/// Checks the references a level makes into its own node list and normalizes connection
+/// pairs of undirected levels to (min, max), which is what the gameplay systems compare
+/// against. `field_prefix` is put in front of the field an error names, e.g. `levels[2].`.
+pub fn validate_level(path: &Path, field_prefix: &str, mut spec: PuzzleSpec) -> Result<PuzzleSpec, LevelLoadError> {
+    let node_count = spec.node_positions.len();
+    if node_count == 0 {
+        return Err(LevelLoadError::Invalid {
+            path: path.to_path_buf(),
+            field: format!("{}node_positions", field_prefix),
+            message: "a level needs at least one node".to_string(),
+        });
+    }
+
+    let mut sorted: Vec<(usize, usize)> = spec.correct_connections.iter().copied().collect();
+    sorted.sort_unstable();
+    for (a, b) in sorted {
+        if a >= node_count || b >= node_count {
+            return Err(LevelLoadError::Invalid {
+                path: path.to_path_buf(),
+                field: format!("{}correct_connections", field_prefix),
+                message: format!(
+                    "connection ({}, {}) refers to a node that does not exist (level has {} nodes)",
+                    a, b, node_count
+                ),
+            });
+        }
+    }
+
+    if spec.correct_connections.is_empty() && spec.objective != Objective::MatchDegrees && node_count > 1 {
+        return Err(LevelLoadError::Invalid {
+            path: path.to_path_buf(),
+            field: format!("{}correct_connections", field_prefix),
+            message: "only MatchDegrees levels can leave out their connections".to_string(),
+        });
+    }
+
+    if spec.required_degrees.len() > node_count {
+        return Err(LevelLoadError::Invalid {
+            path: path.to_path_buf(),
+            field: format!("{}required_degrees", field_prefix),
+            message: format!(
+                "{} degrees given for {} nodes",
+                spec.required_degrees.len(),
+                node_count
+            ),
+        });
+    }
+
+    if spec.node_kinds.len() > node_count {
+        return Err(LevelLoadError::Invalid {
+            path: path.to_path_buf(),
+            field: format!("{}node_kinds", field_prefix),
+            message: format!(
+                "{} kinds given for {} nodes",
+                spec.node_kinds.len(),
+                node_count
+            ),
+        });
+    }
+
+    for node in spec.objective.node_ids() {
+        if node >= node_count {
+            return Err(LevelLoadError::Invalid {
+                path: path.to_path_buf(),
+                field: format!("{}objective", field_prefix),
+                message: format!("node {} does not exist (level has {} nodes)", node, node_count),
+            });
+        }
+    }
+
+    // Directed levels keep each pair in the order written
+    if !spec.directed {
+        spec.correct_connections = spec
+            .correct_connections
+            .iter()
+            .map(|&(a, b)| normalize_pair(a, b))
+            .collect();
+    }
+
+    Ok(spec)
+}
+
+/// Reads and parses a single level file from disk.
+pub fn load_level_file(path: &Path) -> Result<PuzzleSpec, LevelLoadError> {
+    let contents = fs::read_to_string(path).map_err(|source| LevelLoadError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    parse_level(path, &contents)
+}
+
+fn files_in_dir(dir: &Path, filter: fn(&Path) -> bool) -> Result<Vec<PathBuf>, LevelLoadError> {
+    let entries = fs::read_dir(dir).map_err(|source| LevelLoadError::Io {
+        path: dir.to_path_buf(),
+        source,
+    })?;
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && filter(path))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Lists the single-level files in `dir`, sorted by file name.
+pub fn level_files_in_dir(dir: &Path) -> Result<Vec<PathBuf>, LevelLoadError> {
+    files_in_dir(dir, is_level_file)
+}
+
+/// Lists the level pack files in `dir`, sorted by file name.
+pub fn level_pack_files_in_dir(dir: &Path) -> Result<Vec<PathBuf>, LevelLoadError> {
+    files_in_dir(dir, is_level_pack_file)
+}
+
+/// Loads every level in `dir`. Files that fail to load are reported in the second
+/// list and left out of the first, so one broken file does not take the others down.
+pub fn load_levels_from_dir(dir: &Path) -> (Vec<PuzzleSpec>, Vec<LevelLoadError>) {
+    let files = match level_files_in_dir(dir) {
+        Ok(files) => files,
+        Err(err) => return (Vec::new(), vec![err]),
+    };
+
+    let mut levels = Vec::new();
+    let mut errors = Vec::new();
+    for path in files {
+        match load_level_file(&path) {
+            Ok(spec) => levels.push(spec),
+            Err(err) => errors.push(err),
+        }
+    }
+    (levels, errors)
+}
@@ -0,0 +1,45 @@
+//! # EchoNet Game
+//!
+//! Game logic for EchoNet, shared by the game binary in `main.rs`
+//! and the level tools under `src/bin/`.
+//!
+//! Apps embedding the puzzle add `EchoNetPlugin` after `DefaultPlugins`:
+//!
+//! ```no_run
+//! use bevy::prelude::*;
+//! use echonet_game::{EchoNetConfig, EchoNetPlugin};
+//!
+//! App::new()
+//!     .add_plugins(DefaultPlugins)
+//!     .add_plugins(EchoNetPlugin {
+//!         config: EchoNetConfig { use_menu: false, ..default() },
+//!     })
+//!     .run();
+//! ```
+
+// Bevy systems routinely take many parameters and nested query filters
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
+pub mod board;
+pub mod components;
+pub mod difficulty;
+pub mod echonet_plugin;
+pub mod game_state;
+pub mod gameplay_plugin;
+pub mod generator;
+pub mod goals;
+pub mod levels;
+pub mod menu_plugin;
+pub mod propagation;
+pub mod resources;
+pub mod scoring;
+pub mod solver;
+pub mod ui_plugin;
+pub mod validation;
+
+pub use echonet_plugin::{EchoNetConfig, EchoNetPlugin};
+pub use resources::LevelSource;
+
+// Size of the primary window created in main.rs; generated puzzles are laid out to fit it
+pub const WINDOW_WIDTH: f32 = 800.0;
+pub const WINDOW_HEIGHT: f32 = 600.0;
@@ -1,7 +1,8 @@
 use bevy::prelude::*;
 use crate::game_state::GameState;
-use crate::components::{MainMenuUI, MenuButtonAction};
-use crate::resources::GameFont;
+use crate::components::{LevelSelectList, MainMenuUI, MenuButtonAction};
+use crate::difficulty::difficulty;
+use crate::resources::{CurrentLevel, GameFont, LevelLibrary, PuzzleSpec};
 
 pub struct MenuPlugin;
 
@@ -10,7 +11,7 @@ impl Plugin for MenuPlugin {
         app
             .add_systems(OnEnter(GameState::MainMenu), setup_main_menu)
             .add_systems(Update, 
-                (menu_button_interaction_system).run_if(in_state(GameState::MainMenu))
+                (populate_level_select_system, menu_button_interaction_system).run_if(in_state(GameState::MainMenu))
             )
             .add_systems(OnExit(GameState::MainMenu), cleanup_main_menu);
     }
@@ -20,7 +21,7 @@ fn setup_main_menu(mut commands: Commands) {
     let font = default();
     commands.insert_resource(GameFont(font));
 
-    commands.spawn((Camera2d::default(), MainMenuUI));
+    commands.spawn((Camera2d, MainMenuUI));
 
     commands.spawn((
         Node {
@@ -46,6 +47,17 @@ fn setup_main_menu(mut commands: Commands) {
             },
         ));
 
+        // Filled by populate_level_select_system once the level library is loaded
+        parent.spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                margin: UiRect::bottom(Val::Px(30.0)),
+                ..default()
+            },
+            LevelSelectList,
+        ));
+
         parent.spawn((
             Button,
             Node {
@@ -93,6 +105,52 @@ fn setup_main_menu(mut commands: Commands) {
     });
 }
 
+fn level_select_label(level_id: usize, spec: &PuzzleSpec) -> String {
+    let title = if spec.title.is_empty() { "Untitled" } else { spec.title.as_str() };
+    format!("{}. {}  (difficulty {})", level_id + 1, title, difficulty(spec))
+}
+
+/// Lists every authored level with its difficulty rating, rebuilding the list whenever
+/// the level library changes (level packs finish loading after the menu is shown).
+fn populate_level_select_system(
+    mut commands: Commands,
+    level_library: Res<LevelLibrary>,
+    list_query: Query<(Entity, Ref<LevelSelectList>)>,
+) {
+    for (list, marker) in list_query.iter() {
+        if !marker.is_added() && !level_library.is_changed() {
+            continue;
+        }
+        commands.entity(list).despawn_related::<Children>();
+        commands.entity(list).with_children(|parent| {
+            for (level_id, spec) in level_library.levels.iter().enumerate() {
+                parent.spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(420.0),
+                        height: Val::Px(40.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        margin: UiRect::bottom(Val::Px(8.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                    MenuButtonAction::SelectLevel(level_id),
+                )).with_children(|parent| {
+                    parent.spawn((
+                        Text(level_select_label(level_id, spec)),
+                        TextFont {
+                            font_size: 22.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                    ));
+                });
+            }
+        });
+    }
+}
+
 fn menu_button_interaction_system(
     mut interaction_query: Query<
         (&Interaction, &MenuButtonAction, &mut BackgroundColor),
@@ -100,6 +158,7 @@ fn menu_button_interaction_system(
     >,
     mut app_exit_events: EventWriter<AppExit>,
     mut next_game_state: ResMut<NextState<GameState>>,
+    mut current_level: ResMut<CurrentLevel>,
 ) {
     for (interaction, menu_button_action, mut color) in &mut interaction_query {
         match *interaction {
@@ -112,6 +171,10 @@ fn menu_button_interaction_system(
                     MenuButtonAction::Quit => {
                         app_exit_events.write(AppExit::Success);
                     }
+                    MenuButtonAction::SelectLevel(level_id) => {
+                        current_level.level_id = *level_id;
+                        next_game_state.set(GameState::LoadingLevel);
+                    }
                 }
             }
             Interaction::Hovered => {
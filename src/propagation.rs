@@ -0,0 +1,102 @@
+//! Echo propagation over the player's drawn network, as plain data.
+//!
+//! An echo starts at a source node and travels outward along every drawn connection at a
+//! fixed speed, so the time it takes to cross an edge follows from the edge's length.
+//! Time is counted in whole ticks to keep arrival times exact and comparable.
+
+use bevy::math::Vec2;
+
+use crate::board::PuzzleBoard;
+
+/// World units an echo covers per tick.
+pub const ECHO_DISTANCE_PER_TICK: f32 = 10.0;
+/// Ticks of echo travel per second of game time.
+pub const ECHO_TICKS_PER_SECOND: f32 = 30.0;
+
+/// Ticks an echo takes to cross the edge between two node positions; never less than one.
+pub fn travel_ticks(from: Vec2, to: Vec2) -> u32 {
+    ((from.distance(to) / ECHO_DISTANCE_PER_TICK).round() as u32).max(1)
+}
+
+/// When an echo from `source` reaches each node of a board.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EchoArrivals {
+    source: usize,
+    // Indexed by node id; None for nodes the echo cannot reach
+    ticks: Vec<Option<u32>>,
+}
+
+impl EchoArrivals {
+    pub fn source(&self) -> usize {
+        self.source
+    }
+
+    /// The tick the echo first reaches `node`, counting the source as tick 0.
+    pub fn arrival_tick(&self, node: usize) -> Option<u32> {
+        self.ticks.get(node).copied().flatten()
+    }
+
+    /// Whether the echo has reached `node` by `tick`.
+    pub fn reached_by(&self, node: usize, tick: f32) -> bool {
+        self.arrival_tick(node).is_some_and(|arrival| arrival as f32 <= tick)
+    }
+
+    /// Every reachable node in the order the echo arrives, ties broken by node id.
+    pub fn arrival_order(&self) -> Vec<usize> {
+        let mut reached: Vec<(u32, usize)> = self
+            .ticks
+            .iter()
+            .enumerate()
+            .filter_map(|(node, tick)| tick.map(|tick| (tick, node)))
+            .collect();
+        reached.sort_unstable();
+        reached.into_iter().map(|(_, node)| node).collect()
+    }
+
+    /// The last tick at which anything arrives.
+    pub fn last_arrival_tick(&self) -> u32 {
+        self.ticks.iter().flatten().copied().max().unwrap_or(0)
+    }
+}
+
+/// Sends an echo from `source` along the board's drawn connections, only forwards along
+/// one-way ones. Each node is reached by its shortest path, measured in travel ticks.
+/// An out-of-range `source` reaches nothing.
+pub fn propagate_echo(board: &PuzzleBoard, source: usize) -> EchoArrivals {
+    let node_count = board.node_count();
+    let mut ticks: Vec<Option<u32>> = vec![None; node_count];
+    if source >= node_count {
+        return EchoArrivals { source, ticks };
+    }
+
+    let mut adjacency: Vec<Vec<(usize, u32)>> = vec![Vec::new(); node_count];
+    for &(a, b) in board.drawn_connections() {
+        if let (Some(pos_a), Some(pos_b)) = (board.node_position(a), board.node_position(b)) {
+            let cost = travel_ticks(pos_a, pos_b);
+            adjacency[a].push((b, cost));
+            if !board.is_directed() {
+                adjacency[b].push((a, cost));
+            }
+        }
+    }
+
+    // Dijkstra without a heap; levels have a handful of nodes
+    let mut settled = vec![false; node_count];
+    ticks[source] = Some(0);
+    loop {
+        let next = (0..node_count)
+            .filter(|&node| !settled[node])
+            .filter_map(|node| ticks[node].map(|tick| (tick, node)))
+            .min();
+        let Some((tick, node)) = next else { break };
+        settled[node] = true;
+        for &(neighbour, cost) in &adjacency[node] {
+            let candidate = tick + cost;
+            if ticks[neighbour].is_none_or(|current| candidate < current) {
+                ticks[neighbour] = Some(candidate);
+            }
+        }
+    }
+
+    EchoArrivals { source, ticks }
+}
@@ -1,5 +1,13 @@
 use bevy::prelude::*;
+use serde::Deserialize;
 use std::collections::HashSet;
+use std::time::Duration;
+use crate::goals::GraphGoal;
+use crate::scoring::StarThresholds;
+use crate::board::{distance_to_segment, segments_cross, ActionHistory, BoardAction, ConnectError, PuzzleBoard};
+use crate::levels::LEVELS_DIR;
+use crate::propagation::EchoArrivals;
+use crate::solver::find_solutions;
 
 #[derive(Resource, Default)]
 pub struct CurrentLevel {
@@ -7,27 +15,364 @@ pub struct CurrentLevel {
     pub total_levels: usize, // To know when we are at the last level
 }
 
-#[derive(Resource)]
+impl CurrentLevel {
+    // Past the last authored level, levels come from the generator
+    pub fn is_endless(&self) -> bool {
+        self.level_id >= self.total_levels
+    }
+}
+
+#[derive(Resource, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
 pub struct PuzzleSpec {
+    // Display name shown in the HUD; optional in level files
+    #[serde(default)]
+    pub title: String,
+    // Number of moves a perfect solve takes, if the designer set one
+    #[serde(default)]
+    pub par: Option<usize>,
+    // Move-limit levels: more connects and disconnects than this fails the level
+    #[serde(default)]
+    pub max_moves: Option<usize>,
+    // What earns two and three stars; derived from par when left out
+    #[serde(default)]
+    pub star_thresholds: Option<StarThresholds>,
     pub node_positions: Vec<Vec2>,
-    // Tuples of (node_id_1, node_id_2) representing correct connections
+    // Tuples of (node_id_1, node_id_2) representing correct connections. Only
+    // MatchDegrees levels may leave it out.
+    #[serde(default)]
     pub correct_connections: HashSet<(usize, usize)>, 
+    // What counts as solving the level; without one, drawing exactly correct_connections
+    #[serde(default)]
+    pub objective: Objective,
+    // One-way connections: each pair runs from its first node to its second, and the
+    // player's drag direction has to match
+    #[serde(default)]
+    pub directed: bool,
+    // Kind of each node, by node id; nodes past the end of the list are relays
+    #[serde(default)]
+    pub node_kinds: Vec<NodeKind>,
+    // How many connections each node must end up with, by node id; None or missing
+    // entries leave a node unconstrained
+    #[serde(default)]
+    pub required_degrees: Vec<Option<usize>>,
+    // Planar levels: a connection may not cross another or pass through a node
+    #[serde(default)]
+    pub no_crossing: bool,
+    // Walls that no connection may pass through
+    #[serde(default)]
+    pub obstacles: Vec<Obstacle>,
+    // Most wire, in world units, the drawn connections may use in total
+    #[serde(default)]
+    pub cable_budget: Option<f32>,
+    // Time-attack levels: seconds the player has before the level is failed
+    #[serde(default)]
+    pub time_limit: Option<f32>,
+}
+
+// A static shape in world units that blocks connections
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub enum Obstacle {
+    Rect { min: Vec2, max: Vec2 },
+    Circle { center: Vec2, radius: f32 },
+    // Thin walls along consecutive points
+    Polyline { points: Vec<Vec2> },
+}
+
+impl Obstacle {
+    // Whether a connection drawn from `start` to `end` would run into the obstacle
+    pub fn blocks_segment(&self, start: Vec2, end: Vec2) -> bool {
+        match self {
+            Obstacle::Rect { min, max } => {
+                let rect = Rect::from_corners(*min, *max);
+                let corners = [
+                    rect.min,
+                    Vec2::new(rect.max.x, rect.min.y),
+                    rect.max,
+                    Vec2::new(rect.min.x, rect.max.y),
+                ];
+                rect.contains(start)
+                    || rect.contains(end)
+                    || (0..4).any(|i| segments_cross(start, end, corners[i], corners[(i + 1) % 4]))
+            }
+            Obstacle::Circle { center, radius } => distance_to_segment(*center, start, end) < *radius,
+            Obstacle::Polyline { points } => points
+                .windows(2)
+                .any(|wall| segments_cross(start, end, wall[0], wall[1])),
+        }
+    }
+}
+
+// Connection rules for a single node. "From" is where the player's drag starts.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NodeKind {
+    // No restrictions
+    #[default]
+    Relay,
+    // Connections can only start here, and only one of them
+    Source,
+    // Connections can only end here
+    Sink,
+    // Needs exactly this many connections before the level counts as solved
+    Splitter(usize),
+    // Cannot be connected at all
+    Blocker,
+}
+
+// How a level is won. For echo objectives correct_connections is the designer's
+// reference solution rather than the only accepted answer.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub enum Objective {
+    // The drawn connections must be exactly correct_connections
+    #[default]
+    MatchConnections,
+    // An echo from `source` must reach the `order` nodes one after another, each
+    // strictly later than the one before
+    EchoOrder { source: usize, order: Vec<usize> },
+    // An echo from `source` must reach each (node, tick) pair at exactly that tick
+    EchoTicks { source: usize, arrivals: Vec<(usize, u32)> },
+    // Every node with a required degree must have exactly that many connections;
+    // which nodes they connect to is up to the player
+    MatchDegrees,
+    // The drawn network must have the goal's shape, e.g. `Goal(SpanningTree)`
+    Goal(GraphGoal),
+}
+
+impl Objective {
+    pub fn echo_source(&self) -> Option<usize> {
+        match self {
+            Objective::MatchConnections | Objective::MatchDegrees | Objective::Goal(_) => None,
+            Objective::EchoOrder { source, .. } | Objective::EchoTicks { source, .. } => Some(*source),
+        }
+    }
+
+    // Every node id the objective refers to, source first
+    pub fn node_ids(&self) -> Vec<usize> {
+        match self {
+            Objective::MatchConnections | Objective::MatchDegrees => Vec::new(),
+            Objective::Goal(goal) => goal.node_ids(),
+            Objective::EchoOrder { source, order } => {
+                std::iter::once(*source).chain(order.iter().copied()).collect()
+            }
+            Objective::EchoTicks { source, arrivals } => {
+                std::iter::once(*source).chain(arrivals.iter().map(|&(node, _)| node)).collect()
+            }
+        }
+    }
 }
 
 impl Default for PuzzleSpec {
     fn default() -> Self {
         // Default to an empty puzzle or a very simple first level
         PuzzleSpec {
+            title: String::new(),
+            par: None,
+            max_moves: None,
+            star_thresholds: None,
             node_positions: vec![Vec2::new(-100.0, 0.0), Vec2::new(100.0, 0.0)],
             correct_connections: [(0,1)].iter().cloned().collect(),
+            objective: Objective::default(),
+            directed: false,
+            node_kinds: Vec::new(),
+            required_degrees: Vec::new(),
+            no_crossing: false,
+            obstacles: Vec::new(),
+            cable_budget: None,
+            time_limit: None,
         }
     }
 }
 
+// Where load_level_library_system reads levels from
+#[derive(Resource, Debug, Clone)]
+pub enum LevelSource {
+    // Level files and packs in this directory, relative to the asset root
+    Directory(String),
+    // A fixed list supplied by the app embedding the game
+    Inline(Vec<PuzzleSpec>),
+}
+
+impl Default for LevelSource {
+    fn default() -> Self {
+        LevelSource::Directory(LEVELS_DIR.to_string())
+    }
+}
+
+// Every level found under assets/levels, in file name order
+#[derive(Resource, Default)]
+pub struct LevelLibrary {
+    pub levels: Vec<PuzzleSpec>,
+}
+
+// Seeds the puzzles generated once the authored levels run out.
+// Generated level n (counting from the first one after the last level file) uses base_seed + n.
+#[derive(Resource)]
+pub struct EndlessRun {
+    pub base_seed: u64,
+}
+
+impl Default for EndlessRun {
+    fn default() -> Self {
+        EndlessRun { base_seed: 0xEC40_0000 }
+    }
+}
+
+// Set when the running level is rebuilt after its file changed on disk;
+// setup_level_system restores these connections instead of starting empty
+#[derive(Resource, Default)]
+pub struct LevelReload {
+    pub preserved_connections: Option<HashSet<(usize, usize)>>,
+}
+
 #[derive(Resource, Default)]
 pub struct PlayerAttempt {
-    // Tuples of (node_id_1, node_id_2) representing player drawn connections
-    pub drawn_connections: HashSet<(usize, usize)>,
+    // The current level's rules plus the player's drawn connections;
+    // rebuilt from PuzzleSpec by setup_level_system
+    pub board: PuzzleBoard,
+    // What the player has done on this board, for undo/redo
+    pub history: ActionHistory,
+    // Connections drawn or removed so far, including those undone or redone
+    pub moves: usize,
+}
+
+impl PlayerAttempt {
+    pub fn new(board: PuzzleBoard) -> Self {
+        PlayerAttempt { board, history: ActionHistory::default(), moves: 0 }
+    }
+
+    /// Draws a connection and records it so it can be undone.
+    pub fn connect(&mut self, a: usize, b: usize) -> Result<(usize, usize), ConnectError> {
+        let (a, b) = self.board.try_connect(a, b)?;
+        self.history.record(BoardAction::Connect(a, b));
+        self.moves += 1;
+        Ok((a, b))
+    }
+
+    /// Removes a drawn connection and records it so it can be undone.
+    pub fn disconnect(&mut self, a: usize, b: usize) -> bool {
+        // Record the stored direction so undo redraws the same one-way connection
+        let Some((from, to)) = self.board.connection_between(a, b) else {
+            return false;
+        };
+        self.board.disconnect(from, to);
+        self.history.record(BoardAction::Disconnect(from, to));
+        self.moves += 1;
+        true
+    }
+
+    // Undo and redo draw or remove a connection too, so each counts as a move; one the
+    // board refuses changes nothing and is not counted
+    pub fn undo(&mut self) -> Option<BoardAction> {
+        let action = self.history.undo(&mut self.board)?;
+        self.moves += 1;
+        Some(action)
+    }
+
+    pub fn redo(&mut self) -> Option<BoardAction> {
+        let action = self.history.redo(&mut self.board)?;
+        self.moves += 1;
+        Some(action)
+    }
+
+    /// Whether the level's `max_moves` has been exceeded.
+    pub fn out_of_moves(&self, max_moves: Option<usize>) -> bool {
+        max_moves.is_some_and(|max| self.moves > max)
+    }
+}
+
+// Why the level was failed; read by the LevelFailed screen
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LevelFailReason {
+    #[default]
+    TimeUp,
+    OutOfMoves,
+}
+
+// What check_connection_attempt_system does with a connection that is not part of the solution
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WrongConnectionPolicy {
+    // Refuse the connection; nothing is drawn
+    Reject,
+    // Draw it anyway, in a warning color, so the player has to remove it to finish
+    #[default]
+    AcceptHighlighted,
+}
+
+// Wrong connections attempted on the current level; reset by setup_level_system
+#[derive(Resource, Default)]
+pub struct MistakeCounter {
+    pub mistakes: usize,
+}
+
+/// Hints a player can ask for on each level.
+pub const HINTS_PER_LEVEL: usize = 3;
+
+// Hints taken on the current level and over the whole session. `used` is reset by
+// setup_level_system alongside the mistake counter.
+#[derive(Resource, Default)]
+pub struct HintStats {
+    pub used: usize,
+    pub total_used: usize,
+}
+
+impl HintStats {
+    pub fn remaining(&self) -> usize {
+        HINTS_PER_LEVEL.saturating_sub(self.used)
+    }
+}
+
+/// Most solutions kept per level for hints to aim at.
+pub const MAX_HINT_SOLUTIONS: usize = 64;
+
+// Solutions to the current level that hints on levels other than MatchConnections aim at.
+// Solved once by setup_level_system rather than on every hint, since the search is
+// exhaustive; levels too large for the solver get none and so no hints.
+#[derive(Resource, Default)]
+pub struct LevelSolutions {
+    pub solutions: Vec<HashSet<(usize, usize)>>,
+}
+
+impl LevelSolutions {
+    pub fn for_spec(spec: &PuzzleSpec) -> Self {
+        // Those levels are hinted towards correct_connections and need no search
+        if spec.objective == Objective::MatchConnections {
+            return LevelSolutions::default();
+        }
+        let solutions = match find_solutions(spec, MAX_HINT_SOLUTIONS) {
+            Ok(report) => report.solutions.into_iter().map(|solution| solution.into_iter().collect()).collect(),
+            Err(_) => Vec::new(),
+        };
+        LevelSolutions { solutions }
+    }
+}
+
+// The echo currently travelling through the player's network. Restarted from the same
+// source whenever the drawn connections change, and cleared when a level is set up.
+#[derive(Resource, Default)]
+pub struct EchoState {
+    pub arrivals: Option<EchoArrivals>,
+    // Ticks since the echo left its source; fractional so pulses move smoothly
+    pub elapsed_ticks: f32,
+}
+
+impl EchoState {
+    pub fn has_reached(&self, node: usize) -> bool {
+        self.arrivals
+            .as_ref()
+            .is_some_and(|arrivals| arrivals.reached_by(node, self.elapsed_ticks))
+    }
+
+    /// Nodes the echo has reached so far, in the order it reached them.
+    pub fn reached_nodes(&self) -> Vec<usize> {
+        let Some(arrivals) = &self.arrivals else {
+            return Vec::new();
+        };
+        arrivals
+            .arrival_order()
+            .into_iter()
+            .filter(|&node| arrivals.reached_by(node, self.elapsed_ticks))
+            .collect()
+    }
 }
 
 #[derive(Resource)]
@@ -39,15 +384,37 @@ pub struct LevelManager {
     pub total_levels: usize,
 }
 
+// Time spent on the current level. `timer` fires once a second to refresh the HUD clock;
+// `elapsed` is reset by setup_level_system unless the level is being hot reloaded.
 #[derive(Resource)]
 pub struct GameTimer {
     pub timer: Timer,
+    pub elapsed: Duration,
 }
 
 impl Default for GameTimer {
     fn default() -> Self {
         Self {
             timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+            elapsed: Duration::ZERO,
         }
     }
 }
+
+impl GameTimer {
+    pub fn reset(&mut self) {
+        self.timer.reset();
+        self.elapsed = Duration::ZERO;
+    }
+
+    /// Seconds left before `time_limit` runs out, never negative.
+    pub fn remaining_secs(&self, time_limit: f32) -> f32 {
+        (time_limit - self.elapsed.as_secs_f32()).max(0.0)
+    }
+}
+
+/// `m:ss` for a number of seconds, rounded down.
+pub fn format_clock(secs: f32) -> String {
+    let whole = secs.max(0.0) as u64;
+    format!("{}:{:02}", whole / 60, whole % 60)
+}
@@ -0,0 +1,102 @@
+//! How well a level was played: the `LevelResult` recorded when a level is completed and
+//! the star thresholds it is scored against.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::resources::PuzzleSpec;
+
+/// Limits a finished level has to stay within to earn a star rating. Limits left out
+/// are not checked.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct StarGoal {
+    #[serde(default)]
+    pub max_secs: Option<f32>,
+    #[serde(default)]
+    pub max_moves: Option<usize>,
+    #[serde(default)]
+    pub max_mistakes: Option<usize>,
+    #[serde(default)]
+    pub max_hints: Option<usize>,
+}
+
+impl StarGoal {
+    pub fn is_met_by(&self, result: &LevelResult) -> bool {
+        self.max_secs.is_none_or(|max| result.time.as_secs_f32() <= max)
+            && self.max_moves.is_none_or(|max| result.moves <= max)
+            && self.max_mistakes.is_none_or(|max| result.mistakes <= max)
+            && self.max_hints.is_none_or(|max| result.hints_used <= max)
+    }
+}
+
+/// A level's star thresholds: meeting `three` earns three stars, else meeting `two` earns
+/// two, and finishing at all earns one.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct StarThresholds {
+    #[serde(default)]
+    pub three: StarGoal,
+    #[serde(default)]
+    pub two: StarGoal,
+}
+
+impl StarThresholds {
+    /// The level's own thresholds, or ones derived from it. With a par, three stars take
+    /// finishing at or under par and two within half as many moves again. Without one,
+    /// three stars take a run with no mistakes and no hints, and two a run with no hints.
+    pub fn for_spec(spec: &PuzzleSpec) -> Self {
+        if let Some(thresholds) = &spec.star_thresholds {
+            return thresholds.clone();
+        }
+        match spec.par {
+            Some(par) => StarThresholds {
+                three: StarGoal { max_moves: Some(par), ..default() },
+                two: StarGoal { max_moves: Some(par * 3 / 2), ..default() },
+            },
+            None => StarThresholds {
+                three: StarGoal { max_mistakes: Some(0), max_hints: Some(0), ..default() },
+                two: StarGoal { max_hints: Some(0), ..default() },
+            },
+        }
+    }
+
+    pub fn stars_for(&self, result: &LevelResult) -> usize {
+        if self.three.is_met_by(result) {
+            3
+        } else if self.two.is_met_by(result) {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+/// How a level was completed. Written as an event when `PuzzleCompleteEvent` fires and
+/// kept as a resource for the level-complete screen.
+#[derive(Event, Resource, Debug, Clone, PartialEq, Default)]
+pub struct LevelResult {
+    pub level_id: usize,
+    pub time: Duration,
+    pub moves: usize,
+    pub mistakes: usize,
+    pub hints_used: usize,
+    /// 1 to 3, see `StarThresholds`; 0 for a skipped level.
+    pub stars: usize,
+}
+
+impl LevelResult {
+    /// Scores a finished run of `spec` against its star thresholds.
+    pub fn new(spec: &PuzzleSpec, level_id: usize, time: Duration, moves: usize, mistakes: usize, hints_used: usize) -> Self {
+        let mut result = LevelResult { level_id, time, moves, mistakes, hints_used, stars: 0 };
+        result.stars = StarThresholds::for_spec(spec).stars_for(&result);
+        result
+    }
+
+    /// The result for a level left without solving it, e.g. with the debug skip key.
+    pub fn skipped(level_id: usize) -> Self {
+        LevelResult { level_id, ..Default::default() }
+    }
+}
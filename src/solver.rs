@@ -0,0 +1,143 @@
+//! Exhaustive solver for checking that a level has the solution its designer intended.
+//!
+//! The solver tries every set of connections the board rules allow and keeps the ones that
+//! solve the level. It works on `PuzzleBoard`, so node kinds, degrees, obstacles, crossings
+//! and the cable budget all prune the search exactly as they constrain the player.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::board::PuzzleBoard;
+use crate::resources::{Objective, PuzzleSpec};
+
+/// Most candidate connections the solver will search through; every one doubles the work.
+pub const MAX_SOLVER_EDGES: usize = 24;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolverError {
+    /// The level has more possible connections than `MAX_SOLVER_EDGES`.
+    TooLarge { candidate_edges: usize },
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolverError::TooLarge { candidate_edges } => write!(
+                f,
+                "{} possible connections is too many to search (limit {})",
+                candidate_edges, MAX_SOLVER_EDGES
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SolverError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolverReport {
+    /// Every solution found, each as its sorted list of connections, in the order found.
+    pub solutions: Vec<Vec<(usize, usize)>>,
+    /// Whether the level has more solutions than were asked for.
+    pub truncated: bool,
+}
+
+impl SolverReport {
+    pub fn is_unique(&self) -> bool {
+        self.solutions.len() == 1 && !self.truncated
+    }
+
+    /// Solutions other than the level's `correct_connections`. A level without any (a
+    /// MatchDegrees level may leave them out) has no intended solution to compare with,
+    /// so every solution after the first counts.
+    pub fn unintended<'a>(&'a self, spec: &PuzzleSpec) -> impl Iterator<Item = &'a Vec<(usize, usize)>> {
+        let intended = PuzzleBoard::new(spec).correct_connections().clone();
+        let skip = if intended.is_empty() { 1 } else { 0 };
+        self.solutions
+            .iter()
+            .filter(move |solution| intended.is_empty() || solution.iter().copied().collect::<HashSet<_>>() != intended)
+            .skip(skip)
+    }
+}
+
+/// Finds up to `max_solutions` sets of connections that solve `spec`.
+pub fn find_solutions(spec: &PuzzleSpec, max_solutions: usize) -> Result<SolverReport, SolverError> {
+    find_board_solutions(&PuzzleBoard::new(spec), max_solutions)
+}
+
+/// Like `find_solutions`, for the level `board` was built from. Its drawn connections
+/// are ignored; the search starts from an empty board.
+pub fn find_board_solutions(board: &PuzzleBoard, max_solutions: usize) -> Result<SolverReport, SolverError> {
+    let mut board = board.clone();
+    board.clear();
+    let report = SolverReport { solutions: Vec::new(), truncated: false };
+
+    // Only correct_connections itself can match them, so there is nothing to search and
+    // such a level is unique by definition
+    if *board.objective() == Objective::MatchConnections {
+        let mut intended: Vec<(usize, usize)> = board.correct_connections().iter().copied().collect();
+        intended.sort_unstable();
+        let mut search = Search { candidates: intended, max_solutions, report };
+        let mut attempt = board;
+        if search.candidates.iter().all(|&(a, b)| attempt.restore_connection(a, b).is_ok()) {
+            search.record(&attempt);
+        }
+        return Ok(search.report);
+    }
+
+    let candidates = board.candidate_edges();
+    if candidates.len() > MAX_SOLVER_EDGES {
+        return Err(SolverError::TooLarge { candidate_edges: candidates.len() });
+    }
+    let mut search = Search { candidates, max_solutions, report };
+    search.explore(board, 0);
+    Ok(search.report)
+}
+
+/// Whether `spec` has exactly one solution.
+pub fn has_unique_solution(spec: &PuzzleSpec) -> Result<bool, SolverError> {
+    Ok(find_solutions(spec, 2)?.is_unique())
+}
+
+fn sorted_connections(board: &PuzzleBoard) -> Vec<(usize, usize)> {
+    let mut connections: Vec<(usize, usize)> = board.drawn_connections().iter().copied().collect();
+    connections.sort_unstable();
+    connections
+}
+
+struct Search {
+    candidates: Vec<(usize, usize)>,
+    max_solutions: usize,
+    report: SolverReport,
+}
+
+impl Search {
+    fn record(&mut self, board: &PuzzleBoard) {
+        if !board.is_solved() {
+            return;
+        }
+        if self.report.solutions.len() == self.max_solutions {
+            self.report.truncated = true;
+        } else {
+            self.report.solutions.push(sorted_connections(board));
+        }
+    }
+
+    // Decides candidates[next..] one at a time: first without the connection, then with it
+    // if the board still accepts it
+    fn explore(&mut self, board: PuzzleBoard, next: usize) {
+        if self.report.truncated {
+            return;
+        }
+        let Some(&(a, b)) = self.candidates.get(next) else {
+            self.record(&board);
+            return;
+        };
+
+        let mut with_edge = board.clone();
+        let accepted = with_edge.restore_connection(a, b).is_ok();
+        self.explore(board, next + 1);
+        if accepted {
+            self.explore(with_edge, next + 1);
+        }
+    }
+}
@@ -1,8 +1,8 @@
 use bevy::prelude::*;
 use crate::game_state::GameState;
-use crate::resources::{CurrentLevel, GameFont};
-use crate::components::{LevelCompleteUI, GameButtonAction};
-use crate::gameplay_plugin::PuzzleCompleteEvent;
+use crate::resources::{format_clock, CurrentLevel, GameTimer, LevelFailReason, PlayerAttempt, PuzzleSpec};
+use crate::scoring::LevelResult;
+use crate::components::{LevelCompleteUI, LevelFailedUI, GameButtonAction};
 
 pub struct UiPlugin;
 
@@ -10,37 +10,23 @@ impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
         app
             .add_systems(OnEnter(GameState::LevelComplete), setup_level_complete_ui)
+            .add_systems(OnEnter(GameState::LevelFailed), setup_level_failed_ui)
             .add_systems(Update, 
-                (level_complete_button_interaction_system).run_if(in_state(GameState::LevelComplete))
+                (game_button_interaction_system)
+                    .run_if(in_state(GameState::LevelComplete).or(in_state(GameState::LevelFailed)))
             )
             .add_systems(OnExit(GameState::LevelComplete), cleanup_level_complete_ui)
-            .add_systems(Update, handle_puzzle_complete_event);
-    }
-}
-
-fn handle_puzzle_complete_event(
-    mut puzzle_complete_reader: EventReader<PuzzleCompleteEvent>,
-    mut next_game_state: ResMut<NextState<GameState>>,
-    game_state: Res<bevy::state::state::State<GameState>>,
-) {
-    if *game_state.get() == GameState::LevelComplete {
-        return;
-    }
-    
-    for _ in puzzle_complete_reader.read() {
-        if *game_state.get() == GameState::Playing {
-            next_game_state.set(GameState::LevelComplete);
-            break;
-        }
+            .add_systems(OnExit(GameState::LevelFailed), cleanup_level_failed_ui);
     }
 }
 
 fn setup_level_complete_ui(
     mut commands: Commands, 
-    game_font: Res<GameFont>, 
-    current_level: Res<CurrentLevel>
+    current_level: Res<CurrentLevel>,
+    level_result: Res<LevelResult>,
+    puzzle_spec: Res<PuzzleSpec>,
 ) {
-    commands.spawn((Camera2d::default(), LevelCompleteUI));
+    commands.spawn((Camera2d, LevelCompleteUI));
 
     commands.spawn((
         Node {
@@ -55,7 +41,11 @@ fn setup_level_complete_ui(
         LevelCompleteUI,
     )).with_children(|parent| {
         parent.spawn((
-            Text(format!("Level {} Complete!", current_level.level_id + 1)),
+            Text(if current_level.is_endless() {
+                format!("Endless {} Complete!", current_level.level_id - current_level.total_levels + 1)
+            } else {
+                format!("Level {} Complete!", current_level.level_id + 1)
+            }),
             TextFont {
                 font_size: 60.0,
                 ..default()
@@ -67,32 +57,47 @@ fn setup_level_complete_ui(
             },
         ));
 
-        if current_level.level_id < current_level.total_levels - 1 {
+        parent.spawn((
+            Text(star_label(level_result.stars)),
+            TextFont {
+                font_size: 48.0,
+                ..default()
+            },
+            TextColor(Color::srgb(1.0, 0.85, 0.2)),
+            Node {
+                margin: UiRect::bottom(Val::Px(20.0)),
+                ..default()
+            },
+        ));
+
+        let moves_line = match puzzle_spec.par {
+            Some(par) => format!("Moves: {} (par {})", level_result.moves, par),
+            None => format!("Moves: {}", level_result.moves),
+        };
+        let breakdown = [
+            (format!("Time: {}", format_clock(level_result.time.as_secs_f32())), false),
+            (moves_line, puzzle_spec.par.is_some_and(|par| level_result.moves > par)),
+            (format!("Mistakes: {}", level_result.mistakes), level_result.mistakes > 0),
+            (format!("Hints used: {}", level_result.hints_used), level_result.hints_used > 0),
+        ];
+        for (line, flagged) in breakdown {
             parent.spawn((
-                Button,
+                Text(line),
+                TextFont {
+                    font_size: 30.0,
+                    ..default()
+                },
+                TextColor(if flagged { Color::srgb(1.0, 0.6, 0.5) } else { Color::WHITE }),
                 Node {
-                    width: Val::Px(250.0),
-                    height: Val::Px(65.0),
-                    justify_content: JustifyContent::Center,
-                    align_items: AlignItems::Center,
-                    margin: UiRect::bottom(Val::Px(20.0)),
-                    border: UiRect::all(Val::Px(2.0)),
+                    margin: UiRect::bottom(Val::Px(12.0)),
                     ..default()
                 },
-                BorderColor(Color::srgb(0.3, 0.3, 0.7)),
-                BackgroundColor(Color::srgb(0.2, 0.2, 0.6)),
-                GameButtonAction::NextLevel,
-            )).with_children(|parent| {
-                parent.spawn((
-                    Text("Next Level".to_string()),
-                    TextFont {
-                        font_size: 30.0,
-                        ..default()
-                    },
-                    TextColor(Color::WHITE),
-                ));
-            });
-        } else {
+            ));
+        }
+
+        // After the last authored level the button carries on into generated puzzles
+        let last_authored_level = current_level.level_id + 1 == current_level.total_levels;
+        if last_authored_level {
              parent.spawn((
                 Text("All Levels Cleared!".to_string()),
                 TextFont {
@@ -107,6 +112,31 @@ fn setup_level_complete_ui(
             ));
         }
 
+        parent.spawn((
+            Button,
+            Node {
+                width: Val::Px(250.0),
+                height: Val::Px(65.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                margin: UiRect::bottom(Val::Px(20.0)),
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BorderColor(Color::srgb(0.3, 0.3, 0.7)),
+            BackgroundColor(Color::srgb(0.2, 0.2, 0.6)),
+            GameButtonAction::NextLevel,
+        )).with_children(|parent| {
+            parent.spawn((
+                Text(if last_authored_level { "Endless Mode" } else { "Next Level" }.to_string()),
+                TextFont {
+                    font_size: 30.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+
         parent.spawn((
             Button,
             Node {
@@ -133,7 +163,119 @@ fn setup_level_complete_ui(
     });
 }
 
-fn level_complete_button_interaction_system(
+// Filled and empty stars out of three; the default font has no star glyph
+fn star_label(stars: usize) -> String {
+    let stars = stars.min(3);
+    let mut label = "* ".repeat(stars) + &"- ".repeat(3 - stars);
+    label.pop();
+    label
+}
+
+fn setup_level_failed_ui(
+    mut commands: Commands,
+    game_timer: Res<GameTimer>,
+    fail_reason: Res<LevelFailReason>,
+    player_attempt: Res<PlayerAttempt>,
+) {
+    commands.spawn((Camera2d, LevelFailedUI));
+
+    commands.spawn((
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+        LevelFailedUI,
+    )).with_children(|parent| {
+        parent.spawn((
+            Text(match *fail_reason {
+                LevelFailReason::TimeUp => "Time's Up!",
+                LevelFailReason::OutOfMoves => "Out of Moves!",
+            }.to_string()),
+            TextFont {
+                font_size: 60.0,
+                ..default()
+            },
+            TextColor(Color::srgb(1.0, 0.5, 0.4)),
+            Node {
+                margin: UiRect::bottom(Val::Px(30.0)),
+                ..default()
+            },
+        ));
+
+        parent.spawn((
+            Text(format!(
+                "Time: {}   Moves: {}",
+                format_clock(game_timer.elapsed.as_secs_f32()),
+                player_attempt.moves
+            )),
+            TextFont {
+                font_size: 30.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Node {
+                margin: UiRect::bottom(Val::Px(20.0)),
+                ..default()
+            },
+        ));
+
+        parent.spawn((
+            Button,
+            Node {
+                width: Val::Px(250.0),
+                height: Val::Px(65.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                margin: UiRect::bottom(Val::Px(20.0)),
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BorderColor(Color::srgb(0.3, 0.7, 0.3)),
+            BackgroundColor(Color::srgb(0.2, 0.5, 0.2)),
+            GameButtonAction::RestartLevel,
+        )).with_children(|parent| {
+            parent.spawn((
+                Text("Retry".to_string()),
+                TextFont {
+                    font_size: 30.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+
+        parent.spawn((
+            Button,
+            Node {
+                width: Val::Px(250.0),
+                height: Val::Px(65.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BorderColor(Color::srgb(0.7, 0.3, 0.3)),
+            BackgroundColor(Color::srgb(0.6, 0.2, 0.2)),
+            GameButtonAction::BackToMenu,
+        )).with_children(|parent| {
+            parent.spawn((
+                Text("Main Menu".to_string()),
+                TextFont {
+                    font_size: 30.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+    });
+}
+
+fn game_button_interaction_system(
     mut interaction_query: Query<
         (&Interaction, &GameButtonAction, &mut BackgroundColor, &mut BorderColor),
         (Changed<Interaction>, With<Button>),
@@ -146,15 +288,16 @@ fn level_complete_button_interaction_system(
             Interaction::Pressed => {
                 match button_action {
                     GameButtonAction::NextLevel => {
-                        if current_level.level_id < current_level.total_levels - 1 {
-                            current_level.level_id += 1;
-                            next_game_state.set(GameState::LoadingLevel);
-                        }
+                        // Past the last level file, setup_level_system generates the next puzzle
+                        current_level.level_id += 1;
+                        next_game_state.set(GameState::LoadingLevel);
+                    }
+                    GameButtonAction::RestartLevel => {
+                        next_game_state.set(GameState::LoadingLevel);
                     }
                     GameButtonAction::BackToMenu => {
                         next_game_state.set(GameState::MainMenu);
                     }
-                    _ => {} 
                 }
             }
             Interaction::Hovered => {
@@ -163,11 +306,14 @@ fn level_complete_button_interaction_system(
                         *bg_color = BackgroundColor(Color::srgb(0.3, 0.3, 0.7));
                         *border_color = BorderColor(Color::WHITE);
                     }
+                    GameButtonAction::RestartLevel => {
+                        *bg_color = BackgroundColor(Color::srgb(0.3, 0.6, 0.3));
+                        *border_color = BorderColor(Color::WHITE);
+                    }
                     GameButtonAction::BackToMenu => {
                          *bg_color = BackgroundColor(Color::srgb(0.7, 0.3, 0.3));
                          *border_color = BorderColor(Color::WHITE);
                     }
-                    _ => {}
                 }
             }
             Interaction::None => {
@@ -176,11 +322,14 @@ fn level_complete_button_interaction_system(
                         *bg_color = BackgroundColor(Color::srgb(0.2, 0.2, 0.6));
                         *border_color = BorderColor(Color::srgb(0.3, 0.3, 0.7));
                     }
+                    GameButtonAction::RestartLevel => {
+                        *bg_color = BackgroundColor(Color::srgb(0.2, 0.5, 0.2));
+                        *border_color = BorderColor(Color::srgb(0.3, 0.7, 0.3));
+                    }
                     GameButtonAction::BackToMenu => {
                          *bg_color = BackgroundColor(Color::srgb(0.6, 0.2, 0.2));
                         *border_color = BorderColor(Color::srgb(0.7, 0.3, 0.3));
                     }
-                     _ => {}
                 }
             }
         }
@@ -191,4 +340,10 @@ fn cleanup_level_complete_ui(mut commands: Commands, query: Query<Entity, With<L
     for entity in query.iter() {
         commands.entity(entity).despawn();
     }
-}
\ No newline at end of file
+}
+
+fn cleanup_level_failed_ui(mut commands: Commands, query: Query<Entity, With<LevelFailedUI>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,219 @@
+//! Static checks for `PuzzleSpec`s, run by the `validate_levels` tool before levels ship.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use crate::board::{normalize_pair, PuzzleBoard};
+use crate::components::NODE_HIT_RADIUS;
+use crate::resources::{Objective, Obstacle, PuzzleSpec};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelIssue {
+    pub field: String,
+    pub message: String,
+}
+
+impl LevelIssue {
+    fn new(field: &str, message: String) -> Self {
+        LevelIssue { field: field.to_string(), message }
+    }
+}
+
+impl fmt::Display for LevelIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "field `{}`: {}", self.field, self.message)
+    }
+}
+
+fn sorted_connections(spec: &PuzzleSpec) -> Vec<(usize, usize)> {
+    let mut connections: Vec<(usize, usize)> = spec.correct_connections.iter().copied().collect();
+    connections.sort_unstable();
+    connections
+}
+
+/// Reports pairs that appear more than once in a connection list, in either orientation.
+pub fn check_duplicate_connections(connections: &[(usize, usize)]) -> Vec<LevelIssue> {
+    let mut seen: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+    let mut issues = Vec::new();
+    for &(a, b) in connections {
+        let key = normalize_pair(a, b);
+        if let Some(first) = seen.get(&key) {
+            issues.push(LevelIssue::new(
+                "correct_connections",
+                format!("({}, {}) duplicates ({}, {})", a, b, first.0, first.1),
+            ));
+        } else {
+            seen.insert(key, (a, b));
+        }
+    }
+    issues
+}
+
+/// Runs every structural check on a loaded level and returns all problems found.
+pub fn check_level(spec: &PuzzleSpec) -> Vec<LevelIssue> {
+    let mut issues = Vec::new();
+    let node_count = spec.node_positions.len();
+    let connections = sorted_connections(spec);
+
+    for &(a, b) in &connections {
+        if a >= node_count || b >= node_count {
+            issues.push(LevelIssue::new(
+                "correct_connections",
+                format!("({}, {}) refers to a node outside node_positions (level has {} nodes)", a, b, node_count),
+            ));
+        } else if a == b {
+            issues.push(LevelIssue::new(
+                "correct_connections",
+                format!("({}, {}) connects a node to itself", a, b),
+            ));
+        }
+    }
+
+    // Nodes are clickable within NODE_HIT_RADIUS, so two nodes closer than twice that
+    // have overlapping hit circles and a click could land on either.
+    for i in 0..node_count {
+        for j in (i + 1)..node_count {
+            let distance = spec.node_positions[i].distance(spec.node_positions[j]);
+            if distance < NODE_HIT_RADIUS * 2.0 {
+                issues.push(LevelIssue::new(
+                    "node_positions",
+                    format!(
+                        "nodes {} and {} are {:.1} units apart; their {}-unit hit areas overlap",
+                        i, j, distance, NODE_HIT_RADIUS
+                    ),
+                ));
+            }
+        }
+    }
+
+    if spec.time_limit.is_some_and(|limit| limit <= 0.0) {
+        issues.push(LevelIssue::new("time_limit", "must be positive".to_string()));
+    }
+    if let Some(max_moves) = spec.max_moves {
+        // Drawing the reference solution takes one move per connection
+        if max_moves < spec.correct_connections.len() {
+            issues.push(LevelIssue::new(
+                "max_moves",
+                format!("{} moves cannot draw the {} solution connections", max_moves, spec.correct_connections.len()),
+            ));
+        }
+        if spec.par.is_some_and(|par| par > max_moves) {
+            issues.push(LevelIssue::new("par", format!("is more than max_moves ({})", max_moves)));
+        }
+    }
+    if spec.cable_budget.is_some_and(|budget| budget <= 0.0) {
+        issues.push(LevelIssue::new("cable_budget", "must be positive".to_string()));
+    }
+
+    for (idx, obstacle) in spec.obstacles.iter().enumerate() {
+        let field = format!("obstacles[{}]", idx);
+        let malformed = match obstacle {
+            Obstacle::Rect { min, max } => min.x == max.x || min.y == max.y,
+            Obstacle::Circle { radius, .. } => *radius <= 0.0,
+            Obstacle::Polyline { points } => points.len() < 2,
+        };
+        if malformed {
+            issues.push(LevelIssue::new(&field, "shape has no area or length".to_string()));
+        }
+        // A node an obstacle covers could never be reached by any connection
+        for (node, &pos) in spec.node_positions.iter().enumerate() {
+            if obstacle.blocks_segment(pos, pos) {
+                issues.push(LevelIssue::new(&field, format!("covers node {}", node)));
+            }
+        }
+    }
+
+    let objective_in_bounds = spec.objective.node_ids().into_iter().all(|node| node < node_count);
+    if !objective_in_bounds {
+        issues.push(LevelIssue::new(
+            "objective",
+            format!("refers to a node outside node_positions (level has {} nodes)", node_count),
+        ));
+    }
+
+    if spec.objective == Objective::MatchDegrees {
+        let required: Vec<usize> = spec.required_degrees.iter().flatten().copied().collect();
+        if required.is_empty() {
+            issues.push(LevelIssue::new(
+                "required_degrees",
+                "a MatchDegrees level needs at least one required degree".to_string(),
+            ));
+        }
+        // Every connection adds one to the degree of both its ends
+        let fully_constrained = spec.required_degrees.len() == node_count
+            && spec.required_degrees.iter().all(Option::is_some);
+        if fully_constrained && required.iter().sum::<usize>() % 2 == 1 {
+            issues.push(LevelIssue::new(
+                "required_degrees",
+                "required degrees add up to an odd number, so no set of connections can meet them".to_string(),
+            ));
+        }
+    }
+
+    // A MatchDegrees level may come without a reference solution; there is nothing more to check
+    let has_reference_solution = !(connections.is_empty() && spec.objective == Objective::MatchDegrees);
+
+    // The reference solution has to be playable under the node kind rules, meet every
+    // required degree and, for echo and goal objectives, actually meet the objective
+    let connections_in_bounds = connections.iter().all(|&(a, b)| a < node_count && b < node_count && a != b);
+    if has_reference_solution && objective_in_bounds && connections_in_bounds {
+        let mut board = PuzzleBoard::new(spec);
+        for &(a, b) in &connections {
+            if let Err(err) = board.restore_connection(a, b) {
+                issues.push(LevelIssue::new(
+                    "correct_connections",
+                    format!("solution connection ({}, {}) is not allowed: {}", a, b, err),
+                ));
+            }
+        }
+        if !board.degrees_satisfied() {
+            issues.push(LevelIssue::new(
+                "correct_connections",
+                "correct_connections leave a splitter or required degree short".to_string(),
+            ));
+        }
+        if spec.objective != Objective::MatchConnections && !board.objective_met() {
+            issues.push(LevelIssue::new(
+                "objective",
+                "correct_connections do not meet the objective".to_string(),
+            ));
+        }
+    }
+
+    let unreachable = unreachable_nodes(node_count, &connections);
+    if has_reference_solution && !unreachable.is_empty() {
+        issues.push(LevelIssue::new(
+            "correct_connections",
+            format!("nodes {:?} cannot be reached from node 0", unreachable),
+        ));
+    }
+
+    issues
+}
+
+// Walks the solution graph from node 0 and returns the nodes it never visits.
+fn unreachable_nodes(node_count: usize, connections: &[(usize, usize)]) -> Vec<usize> {
+    if node_count == 0 {
+        return Vec::new();
+    }
+
+    let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); node_count];
+    for &(a, b) in connections {
+        if a < node_count && b < node_count {
+            adjacency[a].push(b);
+            adjacency[b].push(a);
+        }
+    }
+
+    let mut visited: HashSet<usize> = HashSet::from([0]);
+    let mut queue = VecDeque::from([0]);
+    while let Some(node) = queue.pop_front() {
+        for &next in &adjacency[node] {
+            if visited.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    (0..node_count).filter(|node| !visited.contains(node)).collect()
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use bevy::math::Vec2;
+use common::square_loop_spec;
+use echonet_game::board::{ConnectError, PuzzleBoard};
+
+fn square_board() -> PuzzleBoard {
+    PuzzleBoard::new(&square_loop_spec())
+}
+
+#[test]
+fn connection_at_finds_the_edge_under_a_point() {
+    let mut board = square_board();
+    board.try_connect(0, 1).unwrap();
+    board.try_connect(2, 1).unwrap();
+
+    assert_eq!(board.connection_at(Vec2::new(0.0, -100.0)), Some((0, 1)));
+    assert_eq!(board.connection_at(Vec2::new(0.0, -94.0)), Some((0, 1)));
+    assert_eq!(board.connection_at(Vec2::new(105.0, 30.0)), Some((1, 2)));
+    assert_eq!(board.connection_at(Vec2::new(0.0, -80.0)), None);
+    // Beyond the end of a segment counts from its endpoint, not the infinite line
+    assert_eq!(board.connection_at(Vec2::new(0.0, 130.0)), None);
+}
+
+#[test]
+fn connection_at_ignores_undrawn_edges() {
+    let mut board = square_board();
+    assert_eq!(board.connection_at(Vec2::new(0.0, -100.0)), None);
+
+    board.try_connect(0, 1).unwrap();
+    assert!(board.disconnect(1, 0));
+    assert_eq!(board.connection_at(Vec2::new(0.0, -100.0)), None);
+}
+
+#[test]
+fn check_connect_reports_why_a_connection_cannot_be_drawn() {
+    let mut board = square_board();
+    assert_eq!(board.check_connect(2, 1), Ok((1, 2)));
+    assert_eq!(board.check_connect(0, 4), Err(ConnectError::UnknownNode(4)));
+    assert_eq!(board.check_connect(2, 2), Err(ConnectError::SelfLoop(2)));
+
+    board.try_connect(2, 1).unwrap();
+    assert_eq!(board.check_connect(1, 2), Err(ConnectError::AlreadyConnected(1, 2)));
+    // Checking never draws anything
+    assert_eq!(board.drawn_connections().len(), 1);
+}
+
+#[test]
+fn try_connect_stores_undirected_pairs_lowest_node_first() {
+    let mut board = square_board();
+    assert_eq!(board.try_connect(3, 0), Ok((0, 3)));
+    assert!(board.is_connected(0, 3));
+    assert!(board.is_connected(3, 0));
+
+    assert_eq!(board.try_connect(0, 3), Err(ConnectError::AlreadyConnected(0, 3)));
+    assert_eq!(board.try_connect(1, 1), Err(ConnectError::SelfLoop(1)));
+    assert_eq!(board.drawn_connections(), &[(0, 3)].into_iter().collect());
+}
+
+#[test]
+fn disconnect_removes_a_connection_either_way_round() {
+    let mut board = square_board();
+    board.try_connect(0, 1).unwrap();
+    board.try_connect(1, 2).unwrap();
+
+    assert!(board.disconnect(2, 1));
+    assert!(!board.is_connected(1, 2));
+    assert!(!board.disconnect(1, 2));
+    assert!(!board.disconnect(0, 2));
+    assert_eq!(board.drawn_connections(), &[(0, 1)].into_iter().collect());
+}
+
+#[test]
+fn is_solved_needs_exactly_the_correct_connections() {
+    let mut board = square_board();
+    assert!(!board.is_solved());
+
+    for (a, b) in [(1, 0), (1, 2), (3, 2)] {
+        board.try_connect(a, b).unwrap();
+    }
+    assert!(!board.is_solved());
+
+    board.try_connect(0, 3).unwrap();
+    assert!(board.is_solved());
+
+    // An extra connection spoils it again
+    board.try_connect(0, 2).unwrap();
+    assert!(!board.is_solved());
+    board.disconnect(0, 2);
+    assert!(board.is_solved());
+}
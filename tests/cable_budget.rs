@@ -0,0 +1,78 @@
+mod common;
+
+use bevy::math::Vec2;
+use common::TestHarness;
+use echonet_game::board::{ConnectError, PuzzleBoard};
+use echonet_game::game_state::GameState;
+use echonet_game::resources::PuzzleSpec;
+use echonet_game::validation::check_level;
+
+// A 300x400 right triangle: the two legs (700 units) are its minimum spanning tree
+fn triangle_spec(cable_budget: Option<f32>) -> PuzzleSpec {
+    PuzzleSpec {
+        title: "Budget".to_string(),
+        node_positions: vec![Vec2::new(0.0, 0.0), Vec2::new(300.0, 0.0), Vec2::new(0.0, 400.0)],
+        correct_connections: [(0, 1), (0, 2)].into_iter().collect(),
+        cable_budget,
+        ..PuzzleSpec::default()
+    }
+}
+
+#[test]
+fn board_tracks_remaining_cable() {
+    let mut board = PuzzleBoard::new(&triangle_spec(Some(700.0)));
+    assert_eq!(board.remaining_cable(), Some(700.0));
+
+    board.try_connect(0, 1).unwrap();
+    assert_eq!(board.cable_used(), 300.0);
+    assert_eq!(board.remaining_cable(), Some(400.0));
+
+    // The hypotenuse is 500 long and no longer fits
+    assert_eq!(
+        board.try_connect(1, 2),
+        Err(ConnectError::OverBudget { length: 500.0, remaining: 400.0 })
+    );
+    board.try_connect(0, 2).unwrap();
+    assert_eq!(board.remaining_cable(), Some(0.0));
+
+    board.disconnect(0, 1);
+    assert_eq!(board.remaining_cable(), Some(300.0));
+
+    assert_eq!(PuzzleBoard::new(&triangle_spec(None)).remaining_cable(), None);
+}
+
+#[test]
+fn validation_rejects_a_budget_the_solution_cannot_meet() {
+    assert!(check_level(&triangle_spec(Some(700.0))).is_empty());
+
+    let fields: Vec<String> = check_level(&triangle_spec(Some(650.0))).into_iter().map(|issue| issue.field).collect();
+    assert_eq!(fields, vec!["correct_connections".to_string()]);
+
+    let fields: Vec<String> = check_level(&triangle_spec(Some(0.0))).into_iter().map(|issue| issue.field).collect();
+    assert!(fields.contains(&"cable_budget".to_string()), "{:?}", fields);
+}
+
+#[test]
+fn over_budget_attempts_are_rejected_in_game() {
+    let mut harness = TestHarness::with_levels(vec![triangle_spec(Some(700.0))]);
+    harness.start_level(0);
+
+    harness.connect(1, 2);
+    harness.step(1);
+    assert!(harness.player_attempt().board.drawn_connections().contains(&(1, 2)));
+
+    // Only 200 units left, so neither leg fits
+    harness.connect(0, 1);
+    harness.step(1);
+    assert!(matches!(
+        harness.rejected_connections().last().map(|event| event.reason),
+        Some(ConnectError::OverBudget { .. })
+    ));
+    assert_eq!(harness.player_attempt().board.drawn_connections().len(), 1);
+
+    harness.app.world_mut().resource_mut::<echonet_game::resources::PlayerAttempt>().board.clear();
+    harness.connect(0, 1);
+    harness.connect(0, 2);
+    harness.step(4);
+    assert_eq!(harness.state(), GameState::LevelComplete);
+}
@@ -0,0 +1,270 @@
+//! Headless `App` harness for driving `GameplayPlugin` without a window or GPU, plus the
+//! small levels the tests share.
+//!
+//! Besides `MinimalPlugins` and `StatesPlugin`, the app gets the handful of headless
+//! plugins the gameplay systems read from: assets (level packs), input and gizmos.
+
+#![allow(dead_code)]
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use bevy::asset::AssetPlugin;
+use bevy::gizmos::GizmoPlugin;
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey};
+use bevy::input::{ButtonState, InputPlugin};
+use bevy::prelude::*;
+use bevy::render::render_resource::Shader;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+
+use echonet_game::game_state::GameState;
+use echonet_game::gameplay_plugin::{ConnectionAttemptEvent, ConnectionRejectedEvent, GameplayPlugin, PuzzleCompleteEvent};
+use echonet_game::components::{ActivatedNode, Connection, HintMarker, Node};
+use echonet_game::board::{Hint, PuzzleBoard};
+use echonet_game::scoring::LevelResult;
+use echonet_game::resources::{CurrentLevel, EchoState, GameTimer, HintStats, LevelSource, MistakeCounter, PlayerAttempt, PuzzleSpec};
+
+/// Every `GameState` the app has been in, in order, with repeats collapsed.
+#[derive(Resource, Default)]
+pub struct StateHistory(pub Vec<GameState>);
+
+/// Every `ConnectionRejectedEvent` written so far.
+#[derive(Resource, Default)]
+pub struct RejectedConnections(pub Vec<ConnectionRejectedEvent>);
+
+/// Every `LevelResult` event written so far.
+#[derive(Resource, Default)]
+pub struct LevelResults(pub Vec<LevelResult>);
+
+/// How many `PuzzleCompleteEvent`s have been written so far.
+#[derive(Resource, Default)]
+pub struct PuzzleCompleteCount(pub usize);
+
+fn record_state(state: Res<State<GameState>>, mut history: ResMut<StateHistory>) {
+    if history.0.last() != Some(state.get()) {
+        history.0.push(state.get().clone());
+    }
+}
+
+fn record_rejections(mut events: EventReader<ConnectionRejectedEvent>, mut rejected: ResMut<RejectedConnections>) {
+    rejected.0.extend(events.read().map(|event| ConnectionRejectedEvent { ..*event }));
+}
+
+fn record_level_results(mut events: EventReader<LevelResult>, mut results: ResMut<LevelResults>) {
+    results.0.extend(events.read().cloned());
+}
+
+fn count_puzzle_complete(mut events: EventReader<PuzzleCompleteEvent>, mut count: ResMut<PuzzleCompleteCount>) {
+    count.0 += events.read().count();
+}
+
+pub const FRAME_TIME: Duration = Duration::from_nanos(16_666_667);
+
+/// Four nodes on the corners of a 200-unit square, anticlockwise from the bottom left,
+/// solved by the path 0-1-2-3.
+pub fn square_spec() -> PuzzleSpec {
+    PuzzleSpec {
+        title: "Square".to_string(),
+        node_positions: vec![
+            Vec2::new(-100.0, -100.0),
+            Vec2::new(100.0, -100.0),
+            Vec2::new(100.0, 100.0),
+            Vec2::new(-100.0, 100.0),
+        ],
+        correct_connections: [(0, 1), (1, 2), (2, 3)].into_iter().collect(),
+        ..PuzzleSpec::default()
+    }
+}
+
+/// `square_spec` closed into a loop, so all four sides make up the solution.
+pub fn square_loop_spec() -> PuzzleSpec {
+    PuzzleSpec {
+        correct_connections: [(0, 1), (1, 2), (2, 3), (0, 3)].into_iter().collect(),
+        ..square_spec()
+    }
+}
+
+/// Three nodes 200 units apart at the base and 150 high, solved by the path 0-1-2.
+pub fn triangle_spec() -> PuzzleSpec {
+    PuzzleSpec {
+        title: "Triangle".to_string(),
+        node_positions: vec![Vec2::new(-100.0, 0.0), Vec2::new(100.0, 0.0), Vec2::new(0.0, 150.0)],
+        correct_connections: [(0, 1), (1, 2)].into_iter().collect(),
+        ..PuzzleSpec::default()
+    }
+}
+
+/// A board for `spec` with `connections` already drawn.
+pub fn board_with(spec: &PuzzleSpec, connections: &[(usize, usize)]) -> PuzzleBoard {
+    let mut board = PuzzleBoard::new(spec);
+    for &(a, b) in connections {
+        board.try_connect(a, b).unwrap();
+    }
+    board
+}
+
+pub struct TestHarness {
+    pub app: App,
+}
+
+impl TestHarness {
+    /// A harness playing the level files under `assets/levels`.
+    pub fn new() -> Self {
+        Self::with_level_source(LevelSource::default())
+    }
+
+    pub fn with_levels(levels: Vec<PuzzleSpec>) -> Self {
+        Self::with_level_source(LevelSource::Inline(levels))
+    }
+
+    pub fn with_level_source(level_source: LevelSource) -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            AssetPlugin::default(),
+            InputPlugin,
+        ))
+        // GizmoPlugin registers its shaders even when nothing renders them
+        .init_asset::<Shader>()
+        .add_plugins(GizmoPlugin)
+        .init_state::<GameState>()
+        .insert_resource(level_source)
+        .add_plugins(GameplayPlugin)
+        // Every frame advances game time by exactly one 60 Hz frame
+        .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME_TIME))
+        .init_resource::<StateHistory>()
+        .init_resource::<PuzzleCompleteCount>()
+        .init_resource::<RejectedConnections>()
+        .init_resource::<LevelResults>()
+        .add_systems(Last, (record_state, count_puzzle_complete, record_rejections, record_level_results));
+
+        // Run Startup so the level library is loaded before the first level
+        app.update();
+        TestHarness { app }
+    }
+
+    pub fn step(&mut self, frames: usize) {
+        for _ in 0..frames {
+            self.app.update();
+        }
+    }
+
+    pub fn state(&self) -> GameState {
+        self.app.world().resource::<State<GameState>>().get().clone()
+    }
+
+    pub fn set_state(&mut self, state: GameState) {
+        self.app.world_mut().resource_mut::<NextState<GameState>>().set(state);
+    }
+
+    pub fn current_level(&self) -> &CurrentLevel {
+        self.app.world().resource::<CurrentLevel>()
+    }
+
+    pub fn puzzle_spec(&self) -> &PuzzleSpec {
+        self.app.world().resource::<PuzzleSpec>()
+    }
+
+    pub fn state_history(&self) -> &[GameState] {
+        &self.app.world().resource::<StateHistory>().0
+    }
+
+    pub fn puzzle_complete_count(&self) -> usize {
+        self.app.world().resource::<PuzzleCompleteCount>().0
+    }
+
+    /// Loads `level_id` the way the level-complete screen does and steps until it is playable.
+    pub fn start_level(&mut self, level_id: usize) {
+        self.app.world_mut().resource_mut::<CurrentLevel>().level_id = level_id;
+        self.set_state(GameState::LoadingLevel);
+        self.step(3);
+    }
+
+    /// Injects a connection attempt as if the player had dragged from `a` to `b`.
+    pub fn connect(&mut self, a: usize, b: usize) {
+        self.app
+            .world_mut()
+            .send_event(ConnectionAttemptEvent { node1_id: a, node2_id: b });
+    }
+
+    pub fn player_attempt(&self) -> &PlayerAttempt {
+        self.app.world().resource::<PlayerAttempt>()
+    }
+
+    pub fn echo_state(&self) -> &EchoState {
+        self.app.world().resource::<EchoState>()
+    }
+
+    /// Marks node `id` as activated, as clicking it would.
+    pub fn activate(&mut self, id: usize) {
+        let world = self.app.world_mut();
+        let mut query = world.query::<(Entity, &Node)>();
+        let entity = query
+            .iter(world)
+            .find(|(_, node_comp)| node_comp.id == id)
+            .map(|(entity, _)| entity)
+            .expect("no node with that id");
+        world.entity_mut(entity).insert(ActivatedNode);
+    }
+
+    pub fn rejected_connections(&self) -> &[ConnectionRejectedEvent] {
+        &self.app.world().resource::<RejectedConnections>().0
+    }
+
+    pub fn mistakes(&self) -> usize {
+        self.app.world().resource::<MistakeCounter>().mistakes
+    }
+
+    /// Seconds of game time spent on the current level.
+    pub fn elapsed_secs(&self) -> f32 {
+        self.app.world().resource::<GameTimer>().elapsed.as_secs_f32()
+    }
+
+    pub fn level_results(&self) -> &[LevelResult] {
+        &self.app.world().resource::<LevelResults>().0
+    }
+
+    pub fn hint_stats(&self) -> &HintStats {
+        self.app.world().resource::<HintStats>()
+    }
+
+    /// The hints currently highlighted on the board.
+    pub fn hint_markers(&mut self) -> Vec<Hint> {
+        let world = self.app.world_mut();
+        world.query::<&HintMarker>().iter(world).map(|marker| marker.hint).collect()
+    }
+
+    /// The node pairs of every `Connection` entity, panicking if two entities mirror the same pair.
+    pub fn connection_entity_pairs(&mut self) -> HashSet<(usize, usize)> {
+        let world = self.app.world_mut();
+        let mut query = world.query::<&Connection>();
+        let mut pairs = HashSet::new();
+        for connection in query.iter(world) {
+            assert!(pairs.insert(connection.node_ids), "duplicate Connection entity for {:?}", connection.node_ids);
+        }
+        pairs
+    }
+
+    /// Holds `keys` down for one frame, then releases them, as a keyboard would report it.
+    pub fn press_keys(&mut self, keys: &[KeyCode]) {
+        self.send_keys(keys, ButtonState::Pressed);
+        self.step(1);
+        self.send_keys(keys, ButtonState::Released);
+        self.step(1);
+    }
+
+    fn send_keys(&mut self, keys: &[KeyCode], state: ButtonState) {
+        for &key_code in keys {
+            self.app.world_mut().send_event(KeyboardInput {
+                key_code,
+                logical_key: Key::Unidentified(NativeKey::Unidentified),
+                state,
+                text: None,
+                repeat: false,
+                window: Entity::PLACEHOLDER,
+            });
+        }
+    }
+}
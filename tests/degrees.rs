@@ -0,0 +1,96 @@
+mod common;
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use bevy::prelude::Text2d;
+use common::{square_spec, TestHarness};
+use echonet_game::board::{ConnectError, PuzzleBoard};
+use echonet_game::components::DegreeBadge;
+use echonet_game::game_state::GameState;
+use echonet_game::levels::parse_level;
+use echonet_game::resources::{Objective, PuzzleSpec};
+use echonet_game::validation::check_level;
+
+// A square whose corners need 2, 1, 1 and 2 connections: 0-1, 0-3 and 2-3 is one answer,
+// 0-2, 0-3 and 1-3 another
+fn bridges_spec() -> PuzzleSpec {
+    PuzzleSpec {
+        title: "Bridges".to_string(),
+        correct_connections: HashSet::new(),
+        objective: Objective::MatchDegrees,
+        required_degrees: vec![Some(2), Some(1), Some(1), Some(2)],
+        ..square_spec()
+    }
+}
+
+fn badge_texts(harness: &mut TestHarness) -> Vec<(usize, String)> {
+    let world = harness.app.world_mut();
+    let mut query = world.query::<(&DegreeBadge, &Text2d)>();
+    let mut texts: Vec<(usize, String)> = query
+        .iter(world)
+        .map(|(badge, text)| (badge.node_id, text.0.clone()))
+        .collect();
+    texts.sort();
+    texts
+}
+
+#[test]
+fn degree_levels_accept_any_matching_network() {
+    let spec = bridges_spec();
+    for solution in [[(0, 1), (0, 3), (2, 3)], [(0, 2), (0, 3), (1, 3)]] {
+        let mut board = PuzzleBoard::new(&spec);
+        for (a, b) in solution {
+            board.try_connect(a, b).unwrap();
+        }
+        assert!(board.is_solved(), "{:?}", solution);
+    }
+
+    let mut board = PuzzleBoard::new(&spec);
+    board.try_connect(3, 2).unwrap();
+    assert_eq!(board.remaining_degree(3), Some(1));
+    assert_eq!(board.remaining_degree(2), Some(0));
+    assert_eq!(board.try_connect(2, 1), Err(ConnectError::DegreeLimit { node: 2, limit: 1 }));
+    assert!(!board.is_solved());
+}
+
+#[test]
+fn badges_count_down_and_the_level_completes() {
+    let mut harness = TestHarness::with_levels(vec![bridges_spec()]);
+    harness.start_level(0);
+    assert_eq!(
+        badge_texts(&mut harness),
+        vec![(0, "2".to_string()), (1, "1".to_string()), (2, "1".to_string()), (3, "2".to_string())]
+    );
+
+    harness.connect(3, 0);
+    harness.step(2);
+    assert_eq!(
+        badge_texts(&mut harness),
+        vec![(0, "1".to_string()), (1, "1".to_string()), (2, "1".to_string()), (3, "1".to_string())]
+    );
+
+    harness.connect(0, 2);
+    harness.connect(1, 3);
+    harness.step(4);
+    assert_eq!(harness.state(), GameState::LevelComplete);
+    assert_eq!(harness.mistakes(), 0);
+}
+
+#[test]
+fn degree_levels_load_without_connections() {
+    let contents = r#"(
+        node_positions: [(-100.0, 0.0), (100.0, 0.0), (0.0, 150.0)],
+        objective: MatchDegrees,
+        required_degrees: [Some(2), Some(1), Some(1)],
+    )"#;
+    let spec = parse_level(Path::new("bridges.ron"), contents).unwrap();
+    assert!(spec.correct_connections.is_empty());
+    assert!(check_level(&spec).is_empty());
+
+    let odd = parse_level(Path::new("bridges.ron"), &contents.replace("Some(2)", "Some(1)")).unwrap();
+    assert_eq!(check_level(&odd).len(), 1);
+
+    // Other objectives still need their connections
+    assert!(parse_level(Path::new("plain.ron"), &contents.replace("objective: MatchDegrees,", "")).is_err());
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use std::path::Path;
+
+use common::square_spec;
+use echonet_game::difficulty::{difficulty, DifficultyBreakdown};
+use echonet_game::generator::{generate_endless_puzzle, ENDLESS_BATCH};
+use echonet_game::goals::GraphGoal;
+use echonet_game::levels::load_level_file;
+use echonet_game::resources::{Objective, PuzzleSpec};
+
+fn authored_level(file: &str) -> PuzzleSpec {
+    load_level_file(&Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/levels").join(file)).unwrap()
+}
+
+#[test]
+fn authored_level_scores_are_pinned() {
+    let first = authored_level("level_01.ron");
+    assert_eq!(
+        DifficultyBreakdown::of(&first),
+        DifficultyBreakdown {
+            node_count: 3,
+            solution_edges: 2,
+            crossing_opportunities: 0,
+            alternatives: 0,
+            search_depth: 3,
+        }
+    );
+    assert_eq!(difficulty(&first), 21);
+
+    let second = authored_level("level_02.ron");
+    assert_eq!(
+        DifficultyBreakdown::of(&second),
+        DifficultyBreakdown {
+            node_count: 5,
+            solution_edges: 4,
+            // The two long diagonals
+            crossing_opportunities: 1,
+            alternatives: 0,
+            search_depth: 10,
+        }
+    );
+    assert_eq!(difficulty(&second), 43);
+}
+
+#[test]
+fn alternative_solutions_make_a_level_easier() {
+    let square = |objective| PuzzleSpec { objective, ..square_spec() };
+    let exact = DifficultyBreakdown::of(&square(Objective::MatchConnections));
+    let any_tree = DifficultyBreakdown::of(&square(Objective::Goal(GraphGoal::SpanningTree)));
+    assert_eq!(exact.crossing_opportunities, 1);
+    assert_eq!(any_tree.alternatives, 10);
+    assert!(any_tree.score() < exact.score());
+}
+
+#[test]
+fn endless_batches_are_played_easiest_first() {
+    let scores: Vec<u32> = (0..ENDLESS_BATCH * 2)
+        .map(|depth| difficulty(&generate_endless_puzzle(7, depth).unwrap()))
+        .collect();
+    for batch in scores.chunks(ENDLESS_BATCH) {
+        assert!(batch.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", scores);
+    }
+    // Same seed, same run
+    assert_eq!(generate_endless_puzzle(7, 3).unwrap(), generate_endless_puzzle(7, 3).unwrap());
+}
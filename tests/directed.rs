@@ -0,0 +1,80 @@
+mod common;
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use bevy::math::Vec2;
+use common::TestHarness;
+use echonet_game::board::{ConnectError, PuzzleBoard};
+use echonet_game::game_state::GameState;
+use echonet_game::levels::parse_level;
+use echonet_game::propagation::propagate_echo;
+use echonet_game::resources::PuzzleSpec;
+
+fn one_way_spec() -> PuzzleSpec {
+    PuzzleSpec {
+        title: "One Way".to_string(),
+        node_positions: vec![Vec2::new(-150.0, 0.0), Vec2::new(0.0, 100.0), Vec2::new(150.0, 0.0)],
+        correct_connections: [(1, 0), (1, 2)].into_iter().collect(),
+        directed: true,
+        ..PuzzleSpec::default()
+    }
+}
+
+#[test]
+fn directed_levels_keep_pair_order_when_loaded() {
+    let contents = r#"(
+        node_positions: [(-150.0, 0.0), (0.0, 100.0), (150.0, 0.0)],
+        correct_connections: [(1, 0), (1, 2)],
+        directed: true,
+    )"#;
+    let spec = parse_level(Path::new("one_way.ron"), contents).unwrap();
+    assert_eq!(spec.correct_connections, HashSet::from([(1, 0), (1, 2)]));
+
+    let undirected = parse_level(Path::new("two_way.ron"), &contents.replace("directed: true,", "")).unwrap();
+    assert_eq!(undirected.correct_connections, HashSet::from([(0, 1), (1, 2)]));
+}
+
+#[test]
+fn direction_matters_for_completion() {
+    let mut board = PuzzleBoard::new(&one_way_spec());
+    board.try_connect(0, 1).unwrap();
+    board.try_connect(1, 2).unwrap();
+    assert!(!board.is_solved());
+    assert!(!board.is_correct(0, 1));
+
+    // A pair holds one connection at most, whichever way it was drawn
+    assert_eq!(board.try_connect(1, 0), Err(ConnectError::AlreadyConnected(0, 1)));
+    assert!(board.disconnect(1, 0));
+    board.try_connect(1, 0).unwrap();
+    assert!(board.is_solved());
+}
+
+#[test]
+fn echo_only_travels_forwards_on_one_way_connections() {
+    let mut board = PuzzleBoard::new(&one_way_spec());
+    board.try_connect(1, 0).unwrap();
+    board.try_connect(1, 2).unwrap();
+
+    assert_eq!(propagate_echo(&board, 1).arrival_order().len(), 3);
+    assert_eq!(propagate_echo(&board, 0).arrival_order(), vec![0]);
+}
+
+#[test]
+fn drag_direction_decides_the_connection() {
+    let mut harness = TestHarness::with_levels(vec![one_way_spec()]);
+    harness.start_level(0);
+
+    harness.connect(0, 1);
+    harness.step(2);
+    assert_eq!(harness.mistakes(), 1);
+    assert_eq!(harness.connection_entity_pairs(), HashSet::from([(0, 1)]));
+
+    // Undoing and redrawing the other way round fixes it
+    harness.press_keys(&[bevy::prelude::KeyCode::ControlLeft, bevy::prelude::KeyCode::KeyZ]);
+    harness.connect(1, 0);
+    harness.connect(1, 2);
+    harness.step(4);
+    assert_eq!(harness.state(), GameState::LevelComplete);
+    assert_eq!(harness.mistakes(), 1);
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use std::path::Path;
+
+use bevy::math::Vec2;
+use common::{board_with, TestHarness};
+use echonet_game::game_state::GameState;
+use echonet_game::levels::{parse_level, LevelLoadError};
+use echonet_game::resources::{Objective, PuzzleSpec};
+use echonet_game::validation::check_level;
+
+// A source in the middle with three targets at distances 100, 200 and 300 (10, 20, 30 ticks)
+fn star_spec(objective: Objective) -> PuzzleSpec {
+    PuzzleSpec {
+        title: "Star".to_string(),
+        par: None,
+        node_positions: vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 0.0),
+            Vec2::new(0.0, -200.0),
+            Vec2::new(-300.0, 0.0),
+        ],
+        correct_connections: [(0, 1), (0, 2), (0, 3)].into_iter().collect(),
+        objective,
+        ..PuzzleSpec::default()
+    }
+}
+
+#[test]
+fn echo_order_accepts_any_network_with_the_right_order() {
+    let spec = star_spec(Objective::EchoOrder { source: 0, order: vec![1, 2, 3] });
+    assert!(board_with(&spec, &[(0, 1), (0, 2), (0, 3)]).is_solved());
+    // Chaining 1-2 instead reaches 2 at 10 + 22 ticks, after 3 at 30
+    assert!(!board_with(&spec, &[(0, 1), (1, 2), (0, 3)]).is_solved());
+    let two_targets = star_spec(Objective::EchoOrder { source: 0, order: vec![1, 2] });
+    assert!(board_with(&two_targets, &[(0, 1), (1, 2)]).is_solved());
+
+    assert!(!board_with(&spec, &[(0, 1), (0, 2)]).is_solved());
+    let reversed = star_spec(Objective::EchoOrder { source: 0, order: vec![3, 2, 1] });
+    assert!(!board_with(&reversed, &[(0, 1), (0, 2), (0, 3)]).is_solved());
+}
+
+#[test]
+fn echo_ticks_require_exact_arrival() {
+    let spec = star_spec(Objective::EchoTicks { source: 0, arrivals: vec![(1, 10), (2, 20)] });
+    assert!(board_with(&spec, &[(0, 1), (0, 2)]).is_solved());
+    assert!(!board_with(&spec, &[(0, 1), (1, 2)]).is_solved());
+    assert!(!board_with(&spec, &[(0, 1)]).is_solved());
+}
+
+#[test]
+fn echo_levels_do_not_count_unlisted_edges_as_mistakes() {
+    let spec = star_spec(Objective::EchoOrder { source: 0, order: vec![1, 2] });
+    let mut harness = TestHarness::with_levels(vec![spec]);
+    harness.start_level(0);
+
+    harness.connect(0, 1);
+    harness.connect(1, 2);
+    harness.step(4);
+    assert_eq!(harness.mistakes(), 0);
+    assert_eq!(harness.state(), GameState::LevelComplete);
+}
+
+#[test]
+fn objective_is_read_from_level_files() {
+    let contents = r#"(
+        node_positions: [(0.0, 0.0), (100.0, 0.0), (0.0, -200.0)],
+        correct_connections: [(0, 1), (0, 2)],
+        objective: EchoOrder(source: 0, order: [1, 2]),
+    )"#;
+    let spec = parse_level(Path::new("echo.ron"), contents).unwrap();
+    assert_eq!(spec.objective, Objective::EchoOrder { source: 0, order: vec![1, 2] });
+    assert!(check_level(&spec).is_empty());
+
+    let out_of_range = contents.replace("order: [1, 2]", "order: [1, 5]");
+    match parse_level(Path::new("echo.ron"), &out_of_range) {
+        Err(LevelLoadError::Invalid { field, .. }) => assert_eq!(field, "objective"),
+        other => panic!("expected an invalid objective, got {:?}", other),
+    }
+}
+
+#[test]
+fn validator_checks_the_reference_solution_meets_the_objective() {
+    let spec = star_spec(Objective::EchoTicks { source: 0, arrivals: vec![(3, 25)] });
+    let issues = check_level(&spec);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].field, "objective");
+}
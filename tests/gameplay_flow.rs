@@ -0,0 +1,172 @@
+mod common;
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use common::{square_spec, triangle_spec, TestHarness};
+use echonet_game::game_state::GameState;
+use echonet_game::gameplay_plugin::level_pack::{LevelPack, LevelPackSources};
+use echonet_game::resources::{LevelLibrary, PuzzleSpec};
+
+fn solve_current_level(harness: &mut TestHarness) {
+    let mut connections: Vec<(usize, usize)> = harness.puzzle_spec().correct_connections.iter().copied().collect();
+    connections.sort_unstable();
+    for (a, b) in connections {
+        harness.connect(a, b);
+    }
+    harness.step(4);
+}
+
+#[test]
+fn every_authored_level_goes_from_loading_to_complete() {
+    let mut harness = TestHarness::new();
+    let total_levels = harness.app.world().resource::<echonet_game::resources::LevelLibrary>().levels.len();
+    assert!(total_levels >= 2, "expected the level files under assets/levels to load");
+
+    for level_id in 0..total_levels {
+        let history_start = harness.state_history().len();
+        let completions_before = harness.puzzle_complete_count();
+
+        harness.start_level(level_id);
+        assert_eq!(harness.state(), GameState::Playing);
+        assert_eq!(harness.current_level().level_id, level_id);
+
+        solve_current_level(&mut harness);
+
+        assert_eq!(harness.puzzle_complete_count(), completions_before + 1, "level {} did not complete", level_id);
+        assert_eq!(harness.state(), GameState::LevelComplete);
+        assert_eq!(
+            &harness.state_history()[history_start..],
+            &[GameState::LoadingLevel, GameState::Playing, GameState::LevelComplete],
+        );
+    }
+}
+
+#[test]
+fn partial_solution_keeps_playing() {
+    let mut harness = TestHarness::with_levels(vec![PuzzleSpec {
+        correct_connections: [(0, 1), (1, 2), (0, 2)].into_iter().collect(),
+        ..triangle_spec()
+    }]);
+    harness.start_level(0);
+
+    harness.connect(0, 1);
+    harness.connect(2, 1);
+    harness.step(4);
+    assert_eq!(harness.state(), GameState::Playing);
+    assert_eq!(harness.puzzle_complete_count(), 0);
+
+    harness.connect(2, 0);
+    harness.step(4);
+    assert_eq!(harness.puzzle_complete_count(), 1);
+    assert_eq!(harness.state(), GameState::LevelComplete);
+}
+
+#[test]
+fn wrong_connection_blocks_completion() {
+    let mut harness = TestHarness::new();
+    harness.start_level(0);
+
+    // Level 1 is solved by 0-1 and 1-2; 0-2 is not part of the solution
+    harness.connect(0, 2);
+    solve_current_level(&mut harness);
+
+    assert_eq!(harness.puzzle_complete_count(), 0);
+    assert_eq!(harness.state(), GameState::Playing);
+}
+
+fn titled_square(title: &str) -> PuzzleSpec {
+    PuzzleSpec { title: title.to_string(), ..square_spec() }
+}
+
+// Adds a pack after the loose levels, the way startup does for every pack file
+fn add_level_pack(harness: &mut TestHarness, levels: Vec<PuzzleSpec>) -> Handle<LevelPack> {
+    let handle = harness.app.world_mut().resource_mut::<Assets<LevelPack>>().add(LevelPack { levels });
+    harness.app.world_mut().resource_mut::<LevelPackSources>().packs.push(handle.clone());
+    handle
+}
+
+// Edits a pack in place, which writes the same `AssetEvent::Modified` a change on disk does
+fn edit_level_pack(harness: &mut TestHarness, handle: &Handle<LevelPack>, edit: impl FnOnce(&mut LevelPack)) {
+    let mut packs = harness.app.world_mut().resource_mut::<Assets<LevelPack>>();
+    edit(packs.get_mut(handle).unwrap());
+}
+
+#[test]
+fn editing_the_level_being_played_reloads_it_and_keeps_connections() {
+    let mut harness = TestHarness::with_levels(vec![titled_square("Loose")]);
+    let pack = add_level_pack(&mut harness, vec![titled_square("Packed")]);
+    edit_level_pack(&mut harness, &pack, |_| {});
+    // Asset events are written at the end of a frame and read in the next
+    harness.step(2);
+    assert_eq!(harness.app.world().resource::<LevelLibrary>().levels.len(), 2);
+
+    harness.start_level(1);
+    assert_eq!(harness.puzzle_spec().title, "Packed");
+    harness.connect(0, 1);
+    harness.connect(3, 2);
+    harness.step(2);
+
+    let history_start = harness.state_history().len();
+    edit_level_pack(&mut harness, &pack, |pack| {
+        pack.levels[0].node_positions[3] = Vec2::new(-150.0, 100.0);
+    });
+    harness.step(4);
+
+    assert_eq!(&harness.state_history()[history_start..], &[GameState::LoadingLevel, GameState::Playing]);
+    assert_eq!(harness.current_level().level_id, 1);
+    assert_eq!(harness.puzzle_spec().node_positions[3], Vec2::new(-150.0, 100.0));
+    let expected: HashSet<(usize, usize)> = [(0, 1), (2, 3)].into_iter().collect();
+    assert_eq!(harness.player_attempt().board.drawn_connections(), &expected);
+    assert_eq!(harness.connection_entity_pairs(), expected);
+}
+
+#[test]
+fn editing_a_pack_during_an_endless_run_keeps_the_level_count() {
+    let mut harness = TestHarness::with_levels(vec![titled_square("Loose")]);
+    let pack = add_level_pack(&mut harness, Vec::new());
+
+    // Past the only authored level the run is endless
+    harness.start_level(1);
+    assert!(harness.current_level().is_endless());
+    let endless_spec = harness.puzzle_spec().clone();
+
+    let history_start = harness.state_history().len();
+    edit_level_pack(&mut harness, &pack, |pack| pack.levels.push(titled_square("Packed")));
+    harness.step(4);
+
+    assert_eq!(harness.app.world().resource::<LevelLibrary>().levels.len(), 2);
+    assert_eq!(harness.current_level().total_levels, 1);
+    assert!(harness.current_level().is_endless());
+    assert_eq!(harness.puzzle_spec(), &endless_spec);
+    assert_eq!(harness.state_history().len(), history_start);
+}
+
+#[test]
+fn shipped_level_files_are_hot_reloadable() {
+    let mut harness = TestHarness::new();
+    let library_at_startup = harness.app.world().resource::<LevelLibrary>().levels.clone();
+
+    // Every level file is also loaded as an asset, off the main thread
+    let files = harness.app.world().resource::<LevelPackSources>().packs.clone();
+    assert_eq!(files.len(), library_at_startup.len());
+    for _ in 0..500 {
+        if harness.app.world().resource::<Assets<LevelPack>>().len() == files.len() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        harness.step(1);
+    }
+    harness.step(2);
+    assert_eq!(harness.app.world().resource::<LevelLibrary>().levels, library_at_startup);
+
+    harness.start_level(0);
+    harness.connect(0, 1);
+    harness.step(2);
+    edit_level_pack(&mut harness, &files[0], |file| file.levels[0].title = "Renamed".to_string());
+    harness.step(4);
+
+    assert_eq!(harness.puzzle_spec().title, "Renamed");
+    assert_eq!(harness.app.world().resource::<LevelLibrary>().levels[0].title, "Renamed");
+    assert_eq!(harness.connection_entity_pairs(), HashSet::from([(0, 1)]));
+}
@@ -0,0 +1,34 @@
+use std::path::Path;
+
+use echonet_game::generator::{generate_endless_puzzle, generate_puzzle, GeneratorSettings};
+use echonet_game::levels::validate_level;
+use echonet_game::validation::check_level;
+
+#[test]
+fn the_same_seed_gives_the_same_puzzle() {
+    let settings = GeneratorSettings::default();
+    let first = generate_puzzle(7, &settings).unwrap();
+    let second = generate_puzzle(7, &settings).unwrap();
+    assert_eq!(first.node_positions, second.node_positions);
+    assert_eq!(first.correct_connections, second.correct_connections);
+
+    let other = generate_puzzle(8, &settings).unwrap();
+    assert_ne!(first.node_positions, other.node_positions);
+}
+
+#[test]
+fn generated_puzzles_pass_validation() {
+    let path = Path::new("generated");
+    for seed in 0..20 {
+        let settings = GeneratorSettings { node_count: 3 + seed as usize % 6, edge_count: 8, ..Default::default() };
+        let spec = generate_puzzle(seed, &settings).unwrap();
+        assert_eq!(spec.node_positions.len(), settings.node_count);
+        let spec = validate_level(path, "", spec).unwrap_or_else(|err| panic!("seed {}: {}", seed, err));
+        assert_eq!(check_level(&spec), Vec::new(), "seed {}", seed);
+    }
+    for depth in 0..10 {
+        let spec = generate_endless_puzzle(3, depth).unwrap();
+        let spec = validate_level(path, "", spec).unwrap_or_else(|err| panic!("depth {}: {}", depth, err));
+        assert_eq!(check_level(&spec), Vec::new(), "depth {}", depth);
+    }
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use common::{square_spec, TestHarness};
+use echonet_game::game_state::GameState;
+use echonet_game::goals::{all_connected, is_hamiltonian_path, is_single_cycle, is_spanning_tree, max_degree, GraphGoal};
+use echonet_game::levels::parse_level;
+use echonet_game::resources::{Objective, PuzzleSpec};
+use echonet_game::validation::check_level;
+
+fn edges(pairs: &[(usize, usize)]) -> HashSet<(usize, usize)> {
+    pairs.iter().copied().collect()
+}
+
+#[test]
+fn all_connected_needs_every_node_reachable() {
+    assert!(all_connected(3, &edges(&[(0, 1), (1, 2)])));
+    assert!(all_connected(3, &edges(&[(0, 1), (1, 2), (0, 2)])));
+    assert!(!all_connected(4, &edges(&[(0, 1), (2, 3)])));
+    assert!(all_connected(1, &edges(&[])));
+    assert!(!all_connected(2, &edges(&[])));
+}
+
+#[test]
+fn spanning_tree_rejects_cycles_and_gaps() {
+    assert!(is_spanning_tree(4, &edges(&[(0, 1), (0, 2), (0, 3)])));
+    assert!(!is_spanning_tree(3, &edges(&[(0, 1), (1, 2), (0, 2)])));
+    // Right edge count, but a triangle leaves node 3 out
+    assert!(!is_spanning_tree(4, &edges(&[(0, 1), (1, 2), (0, 2)])));
+}
+
+#[test]
+fn hamiltonian_path_must_end_at_both_named_nodes() {
+    let path = edges(&[(0, 2), (2, 1), (1, 3)]);
+    assert!(is_hamiltonian_path(4, &path, 0, 3));
+    assert!(is_hamiltonian_path(4, &path, 3, 0));
+    assert!(!is_hamiltonian_path(4, &path, 0, 1));
+    // A star is a spanning tree but not a path
+    assert!(!is_hamiltonian_path(4, &edges(&[(0, 1), (0, 2), (0, 3)]), 1, 2));
+    assert!(!is_hamiltonian_path(4, &path, 0, 9));
+}
+
+#[test]
+fn single_cycle_must_pass_through_every_node() {
+    assert!(is_single_cycle(4, &edges(&[(0, 1), (1, 2), (2, 3), (0, 3)])));
+    assert!(!is_single_cycle(4, &edges(&[(0, 1), (1, 2), (2, 3)])));
+    // Two separate triangles: every degree is 2, but it is two loops
+    assert!(!is_single_cycle(6, &edges(&[(0, 1), (1, 2), (0, 2), (3, 4), (4, 5), (3, 5)])));
+}
+
+#[test]
+fn max_degree_goal_caps_every_node() {
+    let star = edges(&[(0, 1), (0, 2), (0, 3)]);
+    assert_eq!(max_degree(4, &star), 3);
+    assert!(GraphGoal::MaxDegree(3).is_met(4, &star));
+    assert!(!GraphGoal::MaxDegree(2).is_met(4, &star));
+}
+
+fn goal_square(goal: GraphGoal) -> PuzzleSpec {
+    PuzzleSpec { objective: Objective::Goal(goal), ..square_spec() }
+}
+
+#[test]
+fn goal_levels_parse_and_validate() {
+    let contents = r#"(
+        node_positions: [(-100.0, 0.0), (0.0, 0.0), (100.0, 0.0)],
+        correct_connections: [(0, 1), (1, 2)],
+        objective: Goal(HamiltonianPath(from: 0, to: 2)),
+    )"#;
+    let spec = parse_level(Path::new("path.ron"), contents).unwrap();
+    assert_eq!(spec.objective, Objective::Goal(GraphGoal::HamiltonianPath { from: 0, to: 2 }));
+    assert!(check_level(&spec).is_empty());
+
+    // The reference solution is a path, not a loop
+    let fields: Vec<String> = check_level(&goal_square(GraphGoal::SingleCycle))
+        .into_iter()
+        .map(|issue| issue.field)
+        .collect();
+    assert_eq!(fields, vec!["objective".to_string()]);
+}
+
+#[test]
+fn any_spanning_tree_completes_a_spanning_tree_level() {
+    let mut harness = TestHarness::with_levels(vec![goal_square(GraphGoal::SpanningTree)]);
+    harness.start_level(0);
+
+    // Not the reference solution, and not counted as mistakes
+    harness.connect(0, 2);
+    harness.connect(2, 1);
+    harness.step(4);
+    assert_eq!(harness.state(), GameState::Playing);
+
+    harness.connect(3, 0);
+    harness.step(4);
+    assert_eq!(harness.state(), GameState::LevelComplete);
+    assert_eq!(harness.mistakes(), 0);
+}
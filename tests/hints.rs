@@ -0,0 +1,163 @@
+mod common;
+
+use std::collections::HashSet;
+
+use bevy::math::Vec2;
+use bevy::prelude::KeyCode;
+use common::{board_with, square_spec, triangle_spec, TestHarness};
+use echonet_game::board::{Hint, PuzzleBoard};
+use echonet_game::goals::GraphGoal;
+use echonet_game::resources::{LevelSolutions, Objective, PuzzleSpec, HINTS_PER_LEVEL};
+
+#[test]
+fn board_hints_remove_wrong_connections_before_adding_missing_ones() {
+    let mut board = PuzzleBoard::new(&square_spec());
+    assert_eq!(board.hint(&[]), Some(Hint::Add(0, 1)));
+
+    board.try_connect(1, 0).unwrap();
+    board.try_connect(3, 1).unwrap();
+    assert_eq!(board.hint(&[]), Some(Hint::Remove(1, 3)));
+
+    board.disconnect(1, 3);
+    assert_eq!(board.hint(&[]), Some(Hint::Add(1, 2)));
+
+    board.try_connect(1, 2).unwrap();
+    board.try_connect(2, 3).unwrap();
+    assert_eq!(board.hint(&[]), None);
+}
+
+// Applies hints until there are none left, failing if they go round in circles
+fn follow_hints(board: &mut PuzzleBoard, solutions: &[HashSet<(usize, usize)>]) {
+    for _ in 0..20 {
+        match board.hint(solutions) {
+            Some(Hint::Add(a, b)) => {
+                board.try_connect(a, b).unwrap();
+            }
+            Some(Hint::Remove(a, b)) => assert!(board.disconnect(a, b)),
+            None => return,
+        }
+    }
+    panic!("hints never ran out");
+}
+
+#[test]
+fn degree_hints_aim_at_the_nearest_solution_not_correct_connections() {
+    let spec = PuzzleSpec {
+        objective: Objective::MatchDegrees,
+        required_degrees: vec![Some(1); 4],
+        ..square_spec()
+    };
+    // (0, 3) is not in correct_connections, but (0, 3) with (1, 2) also gives every node one
+    let solutions = LevelSolutions::for_spec(&spec).solutions;
+    let mut board = board_with(&spec, &[(0, 3)]);
+    assert_eq!(board.hint(&solutions), Some(Hint::Add(1, 2)));
+
+    follow_hints(&mut board, &solutions);
+    assert!(board.is_solved());
+    assert_eq!(board.drawn_connections(), &[(0, 3), (1, 2)].into_iter().collect());
+}
+
+#[test]
+fn degree_levels_without_connections_are_hinted_from_the_solver() {
+    let spec = PuzzleSpec {
+        correct_connections: HashSet::new(),
+        objective: Objective::MatchDegrees,
+        required_degrees: vec![Some(2); 3],
+        ..triangle_spec()
+    };
+    let solutions = LevelSolutions::for_spec(&spec).solutions;
+    let mut board = PuzzleBoard::new(&spec);
+    assert_eq!(board.hint(&solutions), Some(Hint::Add(0, 1)));
+
+    follow_hints(&mut board, &solutions);
+    assert!(board.is_solved());
+    assert_eq!(board.hint(&solutions), None);
+}
+
+#[test]
+fn goal_hints_remove_connections_no_solution_uses() {
+    let spec = PuzzleSpec {
+        objective: Objective::Goal(GraphGoal::HamiltonianPath { from: 0, to: 3 }),
+        ..square_spec()
+    };
+    // No path from 0 to 3 through every node can use (0, 3) itself
+    let solutions = LevelSolutions::for_spec(&spec).solutions;
+    let mut board = board_with(&spec, &[(0, 1), (0, 3)]);
+    assert_eq!(board.hint(&solutions), Some(Hint::Remove(0, 3)));
+
+    follow_hints(&mut board, &solutions);
+    assert!(board.is_solved());
+    assert_eq!(board.drawn_connections(), &[(0, 1), (1, 2), (2, 3)].into_iter().collect());
+}
+
+#[test]
+fn echo_order_hints_lead_to_a_solved_board() {
+    let spec = PuzzleSpec {
+        title: "Echo".to_string(),
+        node_positions: vec![Vec2::new(0.0, 0.0), Vec2::new(100.0, 0.0), Vec2::new(0.0, -200.0)],
+        objective: Objective::EchoOrder { source: 0, order: vec![1, 2] },
+        ..PuzzleSpec::default()
+    };
+    let solutions = LevelSolutions::for_spec(&spec).solutions;
+    let mut board = PuzzleBoard::new(&spec);
+    assert!(board.hint(&solutions).is_some());
+
+    follow_hints(&mut board, &solutions);
+    assert!(board.is_solved());
+}
+
+#[test]
+fn levels_are_solved_for_hints_once_when_they_load() {
+    assert!(LevelSolutions::for_spec(&square_spec()).solutions.is_empty());
+
+    let spec = PuzzleSpec {
+        objective: Objective::Goal(GraphGoal::HamiltonianPath { from: 0, to: 3 }),
+        ..square_spec()
+    };
+    let mut harness = TestHarness::with_levels(vec![spec]);
+    harness.start_level(0);
+    assert_eq!(harness.app.world().resource::<LevelSolutions>().solutions.len(), 2);
+
+    harness.connect(0, 3);
+    harness.step(1);
+    harness.press_keys(&[KeyCode::KeyH]);
+    assert_eq!(harness.hint_markers(), vec![Hint::Remove(0, 3)]);
+}
+
+#[test]
+fn pressing_h_highlights_a_hint_and_counts_it() {
+    let mut harness = TestHarness::with_levels(vec![square_spec(), square_spec()]);
+    harness.start_level(0);
+    assert_eq!(harness.hint_stats().remaining(), HINTS_PER_LEVEL);
+
+    harness.press_keys(&[KeyCode::KeyH]);
+    assert_eq!(harness.hint_markers(), vec![Hint::Add(0, 1)]);
+    assert_eq!(harness.hint_stats().used, 1);
+
+    // A new hint replaces the one showing
+    harness.connect(0, 2);
+    harness.step(1);
+    harness.press_keys(&[KeyCode::KeyH]);
+    assert_eq!(harness.hint_markers(), vec![Hint::Remove(0, 2)]);
+
+    // The highlight fades after a few seconds
+    harness.step(240);
+    assert!(harness.hint_markers().is_empty());
+}
+
+#[test]
+fn hints_run_out_per_level_and_are_tallied_for_the_session() {
+    let mut harness = TestHarness::with_levels(vec![square_spec(), square_spec()]);
+    harness.start_level(0);
+
+    for _ in 0..HINTS_PER_LEVEL + 2 {
+        harness.press_keys(&[KeyCode::KeyH]);
+    }
+    assert_eq!(harness.hint_stats().used, HINTS_PER_LEVEL);
+    assert_eq!(harness.hint_stats().remaining(), 0);
+
+    harness.start_level(1);
+    assert_eq!(harness.hint_stats().used, 0);
+    harness.press_keys(&[KeyCode::KeyH]);
+    assert_eq!(harness.hint_stats().total_used, HINTS_PER_LEVEL + 1);
+}
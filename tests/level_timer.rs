@@ -0,0 +1,57 @@
+mod common;
+
+use common::{triangle_spec, TestHarness};
+use echonet_game::game_state::GameState;
+use echonet_game::resources::{format_clock, PuzzleSpec};
+
+fn timed_spec(time_limit: Option<f32>) -> PuzzleSpec {
+    PuzzleSpec {
+        title: "Against the clock".to_string(),
+        time_limit,
+        ..triangle_spec()
+    }
+}
+
+#[test]
+fn clock_formats_minutes_and_seconds() {
+    assert_eq!(format_clock(0.0), "0:00");
+    assert_eq!(format_clock(9.9), "0:09");
+    assert_eq!(format_clock(125.0), "2:05");
+}
+
+#[test]
+fn elapsed_time_counts_while_playing_and_stops_when_solved() {
+    let mut harness = TestHarness::with_levels(vec![timed_spec(None)]);
+    harness.start_level(0);
+    harness.step(60);
+    let before_solving = harness.elapsed_secs();
+    assert!((1.0..1.1).contains(&before_solving), "{}", before_solving);
+
+    harness.connect(0, 1);
+    harness.connect(1, 2);
+    harness.step(4);
+    assert_eq!(harness.state(), GameState::LevelComplete);
+    let final_time = harness.elapsed_secs();
+    harness.step(30);
+    assert_eq!(harness.elapsed_secs(), final_time);
+}
+
+#[test]
+fn running_out_of_time_fails_the_level_and_retry_starts_over() {
+    let mut harness = TestHarness::with_levels(vec![timed_spec(Some(1.0))]);
+    harness.start_level(0);
+    harness.connect(0, 1);
+    harness.step(50);
+    assert_eq!(harness.state(), GameState::Playing);
+
+    harness.step(15);
+    assert_eq!(harness.state(), GameState::LevelFailed);
+    assert_eq!(harness.puzzle_complete_count(), 0);
+
+    // The retry button reloads the same level
+    harness.set_state(GameState::LoadingLevel);
+    harness.step(3);
+    assert_eq!(harness.state(), GameState::Playing);
+    assert!(harness.elapsed_secs() < 0.1);
+    assert!(harness.player_attempt().board.drawn_connections().is_empty());
+}
@@ -0,0 +1,81 @@
+use std::path::Path;
+
+use echonet_game::levels::{parse_level, parse_level_pack, LevelLoadError};
+
+// Unwraps a parse failure into the field it names and its message
+fn parse_error(result: Result<impl std::fmt::Debug, LevelLoadError>) -> (String, String) {
+    match result.unwrap_err() {
+        LevelLoadError::Parse { field, message, .. } => (field, message),
+        other => panic!("expected a parse error, got {:?}", other),
+    }
+}
+
+fn invalid_field(result: Result<impl std::fmt::Debug, LevelLoadError>) -> (String, String) {
+    match result.unwrap_err() {
+        LevelLoadError::Invalid { field, message, .. } => (field, message),
+        other => panic!("expected an invalid level, got {:?}", other),
+    }
+}
+
+#[test]
+fn ron_parse_errors_name_the_offending_field() {
+    let ron = r#"(
+        title: "Bad",
+        node_positions: [(0.0, 0.0), (1.0, "two")],
+        correct_connections: [(0, 1)],
+    )"#;
+    let (field, _) = parse_error(parse_level(Path::new("level_01.ron"), ron));
+    assert!(field.starts_with("node_positions[1]"), "{}", field);
+
+    let ron = r#"(
+        title: "Bad",
+        node_positions: [(0.0, 0.0), (1.0, 0.0)],
+        correct_connections: [(0, "one")],
+    )"#;
+    let (field, _) = parse_error(parse_level(Path::new("level_01.ron"), ron));
+    assert!(field.starts_with("correct_connections"), "{}", field);
+}
+
+#[test]
+fn json_parse_errors_name_the_offending_field() {
+    let json = r#"{
+        "title": "Bad",
+        "node_positions": [[0.0, 0.0], [1.0, "two"]],
+        "correct_connections": [[0, 1]]
+    }"#;
+    let (field, _) = parse_error(parse_level(Path::new("level_01.json"), json));
+    assert!(field.starts_with("node_positions[1]"), "{}", field);
+
+    let json = r#"{"title": "Bad", "node_positions": [[0.0, 0.0]], "colour": "red"}"#;
+    let (field, message) = parse_error(parse_level(Path::new("level_01.json"), json));
+    assert_eq!(field, "colour");
+    assert!(message.contains("unknown field"), "{}", message);
+}
+
+#[test]
+fn level_pack_errors_name_the_level() {
+    let ron = r#"(levels: [
+        (title: "Fine", node_positions: [(0.0, 0.0), (1.0, 0.0)], correct_connections: [(0, 1)]),
+        (title: "Bad", node_positions: [(0.0, 0.0), (1.0, 0.0)], correct_connections: [(0, 1)], par: "low"),
+    ])"#;
+    let (field, _) = parse_error(parse_level_pack(Path::new("pack.levelpack.ron"), ron));
+    assert!(field.starts_with("levels[1].par"), "{}", field);
+}
+
+#[test]
+fn connections_to_missing_nodes_are_rejected() {
+    let ron = r#"(
+        title: "Out of bounds",
+        node_positions: [(0.0, 0.0), (1.0, 0.0)],
+        correct_connections: [(0, 1), (1, 2)],
+    )"#;
+    let (field, message) = invalid_field(parse_level(Path::new("level_01.ron"), ron));
+    assert_eq!(field, "correct_connections");
+    assert!(message.contains("(1, 2)"), "{}", message);
+
+    let pack = r#"(levels: [
+        (title: "Out of bounds", node_positions: [(0.0, 0.0)], correct_connections: [(0, 3)]),
+    ])"#;
+    let (field, _) = invalid_field(parse_level_pack(Path::new("pack.levelpack.ron"), pack));
+    assert_eq!(field, "levels[0].correct_connections");
+}
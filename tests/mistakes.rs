@@ -0,0 +1,65 @@
+mod common;
+
+use std::collections::HashSet;
+
+use common::TestHarness;
+use echonet_game::board::ConnectError;
+use echonet_game::components::RejectedConnectionFlash;
+use echonet_game::game_state::GameState;
+use echonet_game::resources::WrongConnectionPolicy;
+
+#[test]
+fn wrong_connection_is_drawn_and_counted_by_default() {
+    let mut harness = TestHarness::new();
+    harness.start_level(0);
+
+    harness.connect(0, 2);
+    harness.step(2);
+    assert_eq!(harness.mistakes(), 1);
+    assert_eq!(harness.connection_entity_pairs(), HashSet::from([(0, 2)]));
+
+    // Invalid attempts are not mistakes, only connections outside the solution
+    harness.connect(2, 0);
+    harness.connect(1, 1);
+    harness.step(2);
+    assert_eq!(harness.mistakes(), 1);
+}
+
+#[test]
+fn reject_policy_refuses_wrong_connections() {
+    let mut harness = TestHarness::new();
+    harness.app.insert_resource(WrongConnectionPolicy::Reject);
+    harness.start_level(0);
+
+    harness.connect(0, 2);
+    harness.connect(2, 0);
+    harness.step(2);
+    assert_eq!(harness.mistakes(), 2);
+    assert!(harness.connection_entity_pairs().is_empty());
+
+    // Each refusal is reported and flashed where the connection would have gone
+    let reasons: Vec<ConnectError> = harness.rejected_connections().iter().map(|event| event.reason).collect();
+    assert_eq!(reasons, vec![ConnectError::Wrong(0, 2), ConnectError::Wrong(0, 2)]);
+    let mut flashes = harness.app.world_mut().query::<&RejectedConnectionFlash>();
+    assert_eq!(flashes.iter(harness.app.world()).count(), 2);
+    harness.step(60);
+    assert_eq!(flashes.iter(harness.app.world()).count(), 0);
+
+    harness.connect(0, 1);
+    harness.connect(1, 2);
+    harness.step(4);
+    assert_eq!(harness.state(), GameState::LevelComplete);
+    assert_eq!(harness.mistakes(), 2);
+}
+
+#[test]
+fn mistakes_reset_for_each_level() {
+    let mut harness = TestHarness::new();
+    harness.start_level(0);
+    harness.connect(0, 2);
+    harness.step(2);
+    assert_eq!(harness.mistakes(), 1);
+
+    harness.start_level(1);
+    assert_eq!(harness.mistakes(), 0);
+}
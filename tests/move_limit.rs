@@ -0,0 +1,108 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::prelude::KeyCode;
+use common::{triangle_spec, TestHarness};
+use echonet_game::game_state::GameState;
+use echonet_game::board::PuzzleBoard;
+use echonet_game::resources::{LevelFailReason, PlayerAttempt, PuzzleSpec};
+use echonet_game::scoring::LevelResult;
+use echonet_game::validation::check_level;
+
+fn limited_spec(max_moves: Option<usize>) -> PuzzleSpec {
+    PuzzleSpec {
+        title: "Few moves".to_string(),
+        par: Some(2),
+        max_moves,
+        ..triangle_spec()
+    }
+}
+
+#[test]
+fn stars_drop_as_moves_pass_par() {
+    let stars = |moves: usize, par: usize| {
+        let spec = PuzzleSpec { par: Some(par), ..limited_spec(None) };
+        LevelResult::new(&spec, 0, Duration::from_secs(60), moves, 3, 2).stars
+    };
+    assert_eq!(stars(2, 2), 3);
+    assert_eq!(stars(1, 2), 3);
+    assert_eq!(stars(3, 2), 2);
+    assert_eq!(stars(4, 2), 1);
+    assert_eq!(stars(6, 4), 2);
+    assert_eq!(stars(7, 4), 1);
+}
+
+#[test]
+fn connects_disconnects_and_undo_all_count_as_moves() {
+    let mut harness = TestHarness::with_levels(vec![limited_spec(None)]);
+    harness.start_level(0);
+
+    harness.connect(0, 2);
+    harness.step(1);
+    harness.press_keys(&[KeyCode::ControlLeft, KeyCode::KeyZ]);
+    harness.press_keys(&[KeyCode::ControlLeft, KeyCode::KeyY]);
+    assert_eq!(harness.player_attempt().moves, 3);
+
+    // A refused connection is not a move
+    harness.connect(0, 0);
+    harness.step(1);
+    assert_eq!(harness.player_attempt().moves, 3);
+}
+
+#[test]
+fn a_refused_undo_is_not_a_move() {
+    // 0-1 takes 200 units of cable and 1-2 180, so only one of them fits at a time
+    let spec = PuzzleSpec { cable_budget: Some(300.0), ..limited_spec(Some(2)) };
+    let mut attempt = PlayerAttempt::new(PuzzleBoard::new(&spec));
+    attempt.connect(0, 1).unwrap();
+    attempt.disconnect(0, 1);
+    assert_eq!(attempt.moves, 2);
+
+    // Drawn outside the history, so undoing the disconnect no longer fits the budget
+    attempt.board.try_connect(1, 2).unwrap();
+    assert_eq!(attempt.undo(), None);
+    assert_eq!(attempt.moves, 2);
+    assert!(!attempt.out_of_moves(spec.max_moves));
+
+    attempt.board.disconnect(1, 2);
+    assert!(attempt.undo().is_some());
+    assert_eq!(attempt.moves, 3);
+    assert!(attempt.out_of_moves(spec.max_moves));
+}
+
+#[test]
+fn exceeding_max_moves_fails_the_level() {
+    let mut harness = TestHarness::with_levels(vec![limited_spec(Some(3))]);
+    harness.start_level(0);
+
+    harness.connect(0, 2);
+    harness.connect(0, 1);
+    harness.connect(1, 2);
+    harness.step(4);
+    assert_eq!(harness.state(), GameState::Playing);
+    assert_eq!(harness.player_attempt().moves, 3);
+
+    harness.connect(2, 1);
+    harness.press_keys(&[KeyCode::ControlLeft, KeyCode::KeyZ]);
+    harness.step(3);
+    assert_eq!(harness.state(), GameState::LevelFailed);
+    assert_eq!(*harness.app.world().resource::<LevelFailReason>(), LevelFailReason::OutOfMoves);
+}
+
+#[test]
+fn the_last_allowed_move_can_still_solve_the_level() {
+    let mut harness = TestHarness::with_levels(vec![limited_spec(Some(2))]);
+    harness.start_level(0);
+    harness.connect(0, 1);
+    harness.connect(1, 2);
+    harness.step(4);
+    assert_eq!(harness.state(), GameState::LevelComplete);
+}
+
+#[test]
+fn move_limits_must_leave_room_for_the_solution() {
+    assert!(check_level(&limited_spec(Some(2))).is_empty());
+    let fields: Vec<String> = check_level(&limited_spec(Some(1))).into_iter().map(|issue| issue.field).collect();
+    assert_eq!(fields, vec!["max_moves".to_string(), "par".to_string()]);
+}
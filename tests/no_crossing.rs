@@ -0,0 +1,77 @@
+mod common;
+
+use bevy::math::Vec2;
+use common::TestHarness;
+use echonet_game::board::{segments_cross, ConnectError, PuzzleBoard};
+use echonet_game::components::ConnectionFlash;
+use echonet_game::resources::PuzzleSpec;
+
+// Corners of a square plus a node in the middle of its bottom edge
+fn planar_spec() -> PuzzleSpec {
+    PuzzleSpec {
+        title: "Planar".to_string(),
+        node_positions: vec![
+            Vec2::new(-100.0, -100.0),
+            Vec2::new(100.0, -100.0),
+            Vec2::new(100.0, 100.0),
+            Vec2::new(-100.0, 100.0),
+            Vec2::new(0.0, -100.0),
+        ],
+        correct_connections: [(0, 2), (1, 2), (0, 4), (1, 4)].into_iter().collect(),
+        no_crossing: true,
+        ..PuzzleSpec::default()
+    }
+}
+
+#[test]
+fn segment_crossing() {
+    let (a, b) = (Vec2::new(-1.0, -1.0), Vec2::new(1.0, 1.0));
+    assert!(segments_cross(a, b, Vec2::new(-1.0, 1.0), Vec2::new(1.0, -1.0)));
+    // Touching at an end point is not a crossing
+    assert!(!segments_cross(a, b, b, Vec2::new(2.0, 0.0)));
+    assert!(!segments_cross(a, b, Vec2::new(2.0, 0.0), Vec2::new(3.0, 0.0)));
+}
+
+#[test]
+fn crossing_and_pass_through_are_rejected() {
+    let mut board = PuzzleBoard::new(&planar_spec());
+    board.try_connect(0, 2).unwrap();
+    assert_eq!(board.try_connect(1, 3), Err(ConnectError::Crosses(0, 2)));
+    // 0-1 runs straight through node 4
+    assert_eq!(board.try_connect(0, 1), Err(ConnectError::PassesThroughNode(4)));
+    // Sharing an end node is fine
+    board.try_connect(2, 1).unwrap();
+
+    let mut relaxed = planar_spec();
+    relaxed.no_crossing = false;
+    let mut board = PuzzleBoard::new(&relaxed);
+    board.try_connect(0, 2).unwrap();
+    board.try_connect(1, 3).unwrap();
+}
+
+#[test]
+fn rejection_event_names_the_blocking_connection_and_flashes_it() {
+    let mut harness = TestHarness::with_levels(vec![planar_spec()]);
+    harness.start_level(0);
+
+    harness.connect(0, 2);
+    harness.step(2);
+    harness.connect(3, 1);
+    harness.step(1);
+
+    let rejected = harness.rejected_connections();
+    assert_eq!(rejected.len(), 1);
+    assert_eq!((rejected[0].node1_id, rejected[0].node2_id), (3, 1));
+    assert_eq!(rejected[0].reason, ConnectError::Crosses(0, 2));
+
+    harness.step(1);
+    let world = harness.app.world_mut();
+    let mut flashing = world.query::<(&echonet_game::components::Connection, &ConnectionFlash)>();
+    let flashing: Vec<(usize, usize)> = flashing.iter(world).map(|(connection, _)| connection.node_ids).collect();
+    assert_eq!(flashing, vec![(0, 2)]);
+
+    // The flash wears off
+    harness.step(60);
+    let world = harness.app.world_mut();
+    assert_eq!(world.query::<&ConnectionFlash>().iter(world).count(), 0);
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use std::path::Path;
+
+use bevy::math::Vec2;
+use bevy::prelude::KeyCode;
+use common::TestHarness;
+use echonet_game::board::{ConnectError, PuzzleBoard};
+use echonet_game::game_state::GameState;
+use echonet_game::levels::parse_level;
+use echonet_game::resources::{NodeKind, PuzzleSpec};
+use echonet_game::validation::check_level;
+
+// A source feeding a splitter that fans out to two sinks, with a blocker off to the side
+fn fan_out_spec() -> PuzzleSpec {
+    PuzzleSpec {
+        title: "Fan Out".to_string(),
+        node_positions: vec![
+            Vec2::new(-200.0, 0.0),
+            Vec2::new(0.0, 0.0),
+            Vec2::new(200.0, 100.0),
+            Vec2::new(200.0, -100.0),
+            Vec2::new(0.0, 200.0),
+        ],
+        correct_connections: [(0, 1), (1, 2), (1, 3)].into_iter().collect(),
+        node_kinds: vec![
+            NodeKind::Source,
+            NodeKind::Splitter(3),
+            NodeKind::Sink,
+            NodeKind::Sink,
+            NodeKind::Blocker,
+        ],
+        ..PuzzleSpec::default()
+    }
+}
+
+#[test]
+fn node_kind_rules() {
+    let mut board = PuzzleBoard::new(&fan_out_spec());
+    assert_eq!(board.try_connect(1, 0), Err(ConnectError::SourceCannotReceive(0)));
+    assert_eq!(board.try_connect(2, 1), Err(ConnectError::SinkCannotSend(2)));
+    assert_eq!(board.try_connect(4, 1), Err(ConnectError::Blocked(4)));
+    assert_eq!(board.try_connect(1, 4), Err(ConnectError::Blocked(4)));
+
+    board.try_connect(0, 1).unwrap();
+    assert_eq!(board.try_connect(0, 2), Err(ConnectError::DegreeLimit { node: 0, limit: 1 }));
+
+    board.try_connect(1, 2).unwrap();
+    board.try_connect(1, 3).unwrap();
+    assert_eq!(board.try_connect(1, 4), Err(ConnectError::Blocked(4)));
+}
+
+#[test]
+fn splitter_needs_exactly_its_count() {
+    let mut spec = fan_out_spec();
+    spec.node_kinds[1] = NodeKind::Splitter(2);
+    let mut board = PuzzleBoard::new(&spec);
+    board.try_connect(0, 1).unwrap();
+    board.try_connect(1, 2).unwrap();
+    assert_eq!(board.try_connect(1, 3), Err(ConnectError::DegreeLimit { node: 1, limit: 2 }));
+
+    // Splitter(3) with the three correct connections completes
+    let mut board = PuzzleBoard::new(&fan_out_spec());
+    for (a, b) in [(0, 1), (1, 2), (1, 3)] {
+        board.try_connect(a, b).unwrap();
+    }
+    assert!(board.is_solved());
+}
+
+#[test]
+fn undo_and_redo_work_when_the_drag_ran_from_the_higher_id() {
+    // Undirected pairs are stored smallest id first, so 1 -> 0 is kept as (0, 1),
+    // which read as a drag would start from the sink
+    let spec = PuzzleSpec {
+        node_positions: vec![Vec2::new(-150.0, 0.0), Vec2::new(0.0, 100.0), Vec2::new(150.0, 0.0)],
+        correct_connections: [(0, 1), (1, 2)].into_iter().collect(),
+        node_kinds: vec![NodeKind::Sink],
+        ..PuzzleSpec::default()
+    };
+    let mut harness = TestHarness::with_levels(vec![spec]);
+    harness.start_level(0);
+
+    harness.connect(0, 1);
+    harness.step(2);
+    assert!(!harness.player_attempt().board.is_connected(0, 1));
+
+    harness.connect(1, 0);
+    harness.step(2);
+    harness.press_keys(&[KeyCode::ControlLeft, KeyCode::KeyZ]);
+    assert!(!harness.player_attempt().board.is_connected(0, 1));
+    harness.press_keys(&[KeyCode::ControlLeft, KeyCode::KeyY]);
+    assert!(harness.player_attempt().board.is_connected(0, 1));
+
+    harness.connect(1, 2);
+    harness.step(4);
+    assert_eq!(harness.state(), GameState::LevelComplete);
+}
+
+#[test]
+fn node_kinds_are_read_and_validated() {
+    let contents = r#"(
+        node_positions: [(-150.0, 0.0), (0.0, 100.0), (150.0, 0.0)],
+        correct_connections: [(0, 1), (1, 2)],
+        node_kinds: [Source, Splitter(2)],
+    )"#;
+    let spec = parse_level(Path::new("kinds.ron"), contents).unwrap();
+    assert_eq!(spec.node_kinds, vec![NodeKind::Source, NodeKind::Splitter(2)]);
+    assert!(check_level(&spec).is_empty());
+
+    let broken = parse_level(Path::new("kinds.ron"), &contents.replace("Splitter(2)", "Blocker")).unwrap();
+    let issues = check_level(&broken);
+    assert_eq!(issues.len(), 2, "{:?}", issues);
+    assert!(issues.iter().all(|issue| issue.field == "correct_connections"));
+
+    assert!(parse_level(Path::new("kinds.ron"), &contents.replace("Splitter(2)]", "Relay, Relay, Sink]")).is_err());
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use std::path::Path;
+
+use bevy::math::Vec2;
+use common::TestHarness;
+use echonet_game::board::{ConnectError, PuzzleBoard};
+use echonet_game::components::ObstacleShape;
+use echonet_game::levels::parse_level;
+use echonet_game::resources::{Obstacle, PuzzleSpec};
+use echonet_game::validation::check_level;
+
+fn walled_spec(obstacles: Vec<Obstacle>) -> PuzzleSpec {
+    PuzzleSpec {
+        title: "Walls".to_string(),
+        node_positions: vec![Vec2::new(-200.0, 0.0), Vec2::new(200.0, 0.0), Vec2::new(0.0, 200.0)],
+        correct_connections: [(0, 2), (1, 2)].into_iter().collect(),
+        obstacles,
+        ..PuzzleSpec::default()
+    }
+}
+
+#[test]
+fn each_obstacle_shape_blocks_the_straight_line_through_it() {
+    let shapes = [
+        Obstacle::Rect { min: Vec2::new(-20.0, -50.0), max: Vec2::new(20.0, 50.0) },
+        Obstacle::Circle { center: Vec2::new(0.0, 10.0), radius: 30.0 },
+        Obstacle::Polyline { points: vec![Vec2::new(0.0, -100.0), Vec2::new(0.0, 100.0)] },
+    ];
+    for obstacle in shapes {
+        let mut board = PuzzleBoard::new(&walled_spec(vec![obstacle.clone()]));
+        assert_eq!(board.try_connect(0, 1), Err(ConnectError::BlockedByObstacle(0)), "{:?}", obstacle);
+        board.try_connect(0, 2).unwrap();
+        assert_eq!(board.obstacle_in_the_way(Vec2::new(-200.0, 0.0), Vec2::new(-100.0, 0.0)), None);
+    }
+}
+
+#[test]
+fn obstacles_are_spawned_and_validated() {
+    let contents = r#"(
+        node_positions: [(-200.0, 0.0), (200.0, 0.0), (0.0, 200.0)],
+        correct_connections: [(0, 2), (1, 2)],
+        obstacles: [
+            Rect(min: (-20.0, -50.0), max: (20.0, 50.0)),
+            Polyline(points: [(-50.0, -150.0), (50.0, -150.0), (50.0, -120.0)]),
+        ],
+    )"#;
+    let spec = parse_level(Path::new("walls.ron"), contents).unwrap();
+    assert_eq!(spec.obstacles.len(), 2);
+    assert!(check_level(&spec).is_empty());
+
+    let mut harness = TestHarness::with_levels(vec![spec]);
+    harness.start_level(0);
+    let world = harness.app.world_mut();
+    assert_eq!(world.query::<&ObstacleShape>().iter(world).count(), 2);
+
+    // A wall right across the solution, and one sitting on top of a node
+    let blocked = walled_spec(vec![
+        Obstacle::Polyline { points: vec![Vec2::new(-150.0, 150.0), Vec2::new(-50.0, 50.0)] },
+        Obstacle::Circle { center: Vec2::new(200.0, 0.0), radius: 10.0 },
+    ]);
+    let fields: Vec<String> = check_level(&blocked).into_iter().map(|issue| issue.field).collect();
+    assert!(fields.contains(&"obstacles[1]".to_string()), "{:?}", fields);
+    assert!(fields.contains(&"correct_connections".to_string()), "{:?}", fields);
+}
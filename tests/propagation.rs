@@ -0,0 +1,82 @@
+mod common;
+
+use bevy::math::Vec2;
+use common::TestHarness;
+use echonet_game::board::PuzzleBoard;
+use echonet_game::propagation::{propagate_echo, travel_ticks, ECHO_TICKS_PER_SECOND};
+use echonet_game::resources::PuzzleSpec;
+
+// Nodes on a line at x = 0, 100, 300 and a detour node above the middle
+fn line_board() -> PuzzleBoard {
+    PuzzleBoard::new(&PuzzleSpec {
+        title: "Line".to_string(),
+        par: None,
+        node_positions: vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 0.0),
+            Vec2::new(300.0, 0.0),
+            Vec2::new(100.0, 150.0),
+        ],
+        correct_connections: [(0, 1), (1, 2)].into_iter().collect(),
+        ..PuzzleSpec::default()
+    })
+}
+
+#[test]
+fn travel_time_follows_edge_length() {
+    assert_eq!(travel_ticks(Vec2::ZERO, Vec2::new(100.0, 0.0)), 10);
+    assert_eq!(travel_ticks(Vec2::ZERO, Vec2::new(0.0, 204.0)), 20);
+    assert_eq!(travel_ticks(Vec2::ZERO, Vec2::new(1.0, 0.0)), 1);
+}
+
+#[test]
+fn echo_takes_the_shortest_path() {
+    let mut board = line_board();
+    board.try_connect(0, 1).unwrap();
+    board.try_connect(1, 2).unwrap();
+    board.try_connect(0, 3).unwrap();
+    board.try_connect(3, 2).unwrap();
+
+    let arrivals = propagate_echo(&board, 0);
+    assert_eq!(arrivals.arrival_tick(0), Some(0));
+    assert_eq!(arrivals.arrival_tick(1), Some(10));
+    assert_eq!(arrivals.arrival_tick(3), Some(18));
+    // 0-1-2 is 10 + 20 ticks; 0-3-2 is 18 + 25
+    assert_eq!(arrivals.arrival_tick(2), Some(30));
+    assert_eq!(arrivals.arrival_order(), vec![0, 1, 3, 2]);
+    assert!(arrivals.reached_by(1, 10.0));
+    assert!(!arrivals.reached_by(2, 29.5));
+}
+
+#[test]
+fn unconnected_nodes_are_never_reached() {
+    let mut board = line_board();
+    board.try_connect(1, 2).unwrap();
+
+    let arrivals = propagate_echo(&board, 0);
+    assert_eq!(arrivals.arrival_order(), vec![0]);
+    assert_eq!(arrivals.arrival_tick(2), None);
+    assert_eq!(propagate_echo(&board, 9).arrival_order(), Vec::<usize>::new());
+}
+
+#[test]
+fn activated_node_sends_an_echo_through_the_level() {
+    let mut harness = TestHarness::new();
+    harness.start_level(1);
+    harness.connect(0, 2);
+    harness.connect(1, 2);
+    harness.step(1);
+
+    harness.activate(0);
+    harness.step(1);
+    assert_eq!(harness.echo_state().reached_nodes(), vec![0]);
+
+    // Node 2 is ~224 units from node 0, so 22 ticks away
+    let frames_to_hub = (22.0 / ECHO_TICKS_PER_SECOND * 60.0) as usize + 1;
+    harness.step(frames_to_hub);
+    assert_eq!(harness.echo_state().reached_nodes(), vec![0, 2]);
+
+    harness.step(frames_to_hub);
+    assert_eq!(harness.echo_state().reached_nodes(), vec![0, 2, 1]);
+    assert!(!harness.echo_state().has_reached(3));
+}
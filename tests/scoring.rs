@@ -0,0 +1,98 @@
+mod common;
+
+use std::path::Path;
+use std::time::Duration;
+
+use bevy::prelude::KeyCode;
+use common::{triangle_spec, TestHarness};
+use echonet_game::levels::parse_level;
+use echonet_game::resources::PuzzleSpec;
+use echonet_game::scoring::{LevelResult, StarGoal, StarThresholds};
+
+fn path_spec(par: Option<usize>, star_thresholds: Option<StarThresholds>) -> PuzzleSpec {
+    PuzzleSpec {
+        title: "Path".to_string(),
+        par,
+        star_thresholds,
+        ..triangle_spec()
+    }
+}
+
+fn result(spec: &PuzzleSpec, secs: u64, moves: usize, mistakes: usize, hints_used: usize) -> LevelResult {
+    LevelResult::new(spec, 0, Duration::from_secs(secs), moves, mistakes, hints_used)
+}
+
+#[test]
+fn default_thresholds_follow_par() {
+    let spec = path_spec(Some(4), None);
+    let stars: Vec<usize> = (1..10).map(|moves| result(&spec, 30, moves, 2, 1).stars).collect();
+    assert_eq!(stars, vec![3, 3, 3, 3, 2, 2, 1, 1, 1]);
+
+    let no_par = path_spec(None, None);
+    assert_eq!(result(&no_par, 30, 9, 0, 0).stars, 3);
+    assert_eq!(result(&no_par, 30, 9, 1, 0).stars, 2);
+    assert_eq!(result(&no_par, 30, 9, 0, 1).stars, 1);
+}
+
+#[test]
+fn level_thresholds_can_require_speed_and_a_clean_run() {
+    let contents = r#"(
+        node_positions: [(-100.0, 0.0), (100.0, 0.0)],
+        correct_connections: [(0, 1)],
+        star_thresholds: Some((
+            three: (max_secs: Some(10.0), max_mistakes: Some(0)),
+            two: (max_secs: Some(30.0)),
+        )),
+    )"#;
+    let spec = parse_level(Path::new("fast.ron"), contents).unwrap();
+    assert_eq!(
+        spec.star_thresholds,
+        Some(StarThresholds {
+            three: StarGoal { max_secs: Some(10.0), max_mistakes: Some(0), ..StarGoal::default() },
+            two: StarGoal { max_secs: Some(30.0), ..StarGoal::default() },
+        })
+    );
+    assert_eq!(result(&spec, 8, 5, 0, 3).stars, 3);
+    assert_eq!(result(&spec, 8, 5, 1, 0).stars, 2);
+    assert_eq!(result(&spec, 31, 1, 0, 0).stars, 1);
+}
+
+#[test]
+fn completing_a_level_emits_its_result() {
+    let mut harness = TestHarness::with_levels(vec![path_spec(Some(2), None)]);
+    harness.start_level(0);
+
+    harness.press_keys(&[KeyCode::KeyH]);
+    harness.connect(0, 2);
+    harness.step(1);
+    harness.press_keys(&[KeyCode::ControlLeft, KeyCode::KeyZ]);
+    harness.connect(0, 1);
+    harness.connect(1, 2);
+    harness.step(4);
+
+    let results = harness.level_results().to_vec();
+    assert_eq!(results.len(), 1);
+    let result = &results[0];
+    assert_eq!((result.level_id, result.moves, result.mistakes, result.hints_used), (0, 4, 1, 1));
+    assert_eq!(result.stars, 1);
+    assert!(result.time > Duration::ZERO);
+    assert_eq!(harness.app.world().resource::<LevelResult>(), result);
+}
+
+#[test]
+fn skipping_a_level_replaces_the_previous_result() {
+    let mut harness = TestHarness::with_levels(vec![path_spec(Some(2), None), path_spec(Some(2), None), path_spec(None, None)]);
+    harness.start_level(0);
+    harness.connect(0, 1);
+    harness.connect(1, 2);
+    harness.step(4);
+    assert_eq!(harness.app.world().resource::<LevelResult>().stars, 3);
+
+    harness.start_level(1);
+    harness.press_keys(&[KeyCode::KeyN]);
+    harness.step(2);
+    assert_eq!(harness.state(), echonet_game::game_state::GameState::LevelComplete);
+    assert_eq!(*harness.app.world().resource::<LevelResult>(), LevelResult::skipped(1));
+    // Only the solved level was reported
+    assert_eq!(harness.level_results().len(), 1);
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use bevy::math::Vec2;
+use common::{square_loop_spec, triangle_spec};
+use echonet_game::goals::GraphGoal;
+use echonet_game::levels::load_levels_from_dir;
+use echonet_game::resources::{NodeKind, Objective, PuzzleSpec};
+use echonet_game::solver::{find_solutions, has_unique_solution, SolverError};
+
+fn square_spec(objective: Objective) -> PuzzleSpec {
+    PuzzleSpec { objective, ..square_loop_spec() }
+}
+
+#[test]
+fn authored_levels_have_unique_solutions() {
+    let (levels, errors) = load_levels_from_dir(&Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/levels"));
+    assert!(errors.is_empty(), "{:?}", errors);
+    for level in &levels {
+        assert_eq!(has_unique_solution(level), Ok(true), "{:?}", level.title);
+    }
+}
+
+#[test]
+fn four_nodes_have_three_hamiltonian_cycles() {
+    let spec = square_spec(Objective::Goal(GraphGoal::SingleCycle));
+    let report = find_solutions(&spec, 10).unwrap();
+    assert_eq!(report.solutions.len(), 3);
+    assert!(!report.truncated);
+    assert_eq!(report.unintended(&spec).count(), 2);
+    assert!(report.solutions.contains(&vec![(0, 1), (0, 3), (1, 2), (2, 3)]));
+}
+
+#[test]
+fn search_stops_once_there_are_more_solutions_than_asked_for() {
+    // 16 spanning trees on four nodes
+    let report = find_solutions(&square_spec(Objective::Goal(GraphGoal::SpanningTree)), 2).unwrap();
+    assert_eq!(report.solutions.len(), 2);
+    assert!(report.truncated);
+    assert!(!report.is_unique());
+}
+
+#[test]
+fn board_rules_narrow_the_solutions() {
+    let mut spec = square_spec(Objective::Goal(GraphGoal::HamiltonianPath { from: 0, to: 3 }));
+    spec.no_crossing = true;
+    let report = find_solutions(&spec, 10).unwrap();
+    // 0-1-2-3 and 0-2-1-3 cross on the square's diagonals; only the perimeter path is left
+    assert_eq!(report.solutions, vec![vec![(0, 1), (1, 2), (2, 3)]]);
+
+    // A blocker makes the cycle impossible
+    spec.objective = Objective::Goal(GraphGoal::SingleCycle);
+    spec.node_kinds = vec![NodeKind::Relay, NodeKind::Blocker];
+    assert!(find_solutions(&spec, 10).unwrap().solutions.is_empty());
+    assert_eq!(has_unique_solution(&spec), Ok(false));
+}
+
+#[test]
+fn oversized_levels_are_refused() {
+    let spec = PuzzleSpec {
+        node_positions: (0..9).map(|idx| Vec2::new(idx as f32 * 60.0, 0.0)).collect(),
+        correct_connections: (0..8).map(|idx| (idx, idx + 1)).collect(),
+        objective: Objective::Goal(GraphGoal::AllConnected),
+        ..PuzzleSpec::default()
+    };
+    assert_eq!(find_solutions(&spec, 2), Err(SolverError::TooLarge { candidate_edges: 36 }));
+}
+
+// A MatchDegrees level that leaves correct_connections out, as levels.rs allows
+fn degrees_only_spec(required_degrees: Vec<Option<usize>>) -> PuzzleSpec {
+    PuzzleSpec {
+        correct_connections: HashSet::new(),
+        objective: Objective::MatchDegrees,
+        required_degrees,
+        ..triangle_spec()
+    }
+}
+
+#[test]
+fn levels_without_a_reference_solution_are_judged_on_uniqueness() {
+    // Only the triangle gives every node two connections
+    let unique = degrees_only_spec(vec![Some(2), Some(2), Some(2)]);
+    let report = find_solutions(&unique, 4).unwrap();
+    assert_eq!(report.solutions, vec![vec![(0, 1), (0, 2), (1, 2)]]);
+    assert_eq!(report.unintended(&unique).count(), 0);
+    assert_eq!(has_unique_solution(&unique), Ok(true));
+
+    // Node 0 needs one connection and the others are free, so several networks work
+    let open = degrees_only_spec(vec![Some(1), None, None]);
+    let report = find_solutions(&open, 10).unwrap();
+    assert!(report.solutions.len() > 1);
+    assert_eq!(report.unintended(&open).count(), report.solutions.len() - 1);
+}
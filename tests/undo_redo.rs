@@ -0,0 +1,131 @@
+mod common;
+
+use std::collections::HashSet;
+
+use bevy::prelude::{KeyCode, Vec2};
+use common::TestHarness;
+use echonet_game::board::{ActionHistory, BoardAction, PuzzleBoard};
+use echonet_game::game_state::GameState;
+use echonet_game::resources::PuzzleSpec;
+
+const UNDO: [KeyCode; 2] = [KeyCode::ControlLeft, KeyCode::KeyZ];
+const REDO: [KeyCode; 2] = [KeyCode::ControlLeft, KeyCode::KeyY];
+
+fn assert_in_sync(harness: &mut TestHarness, expected: &[(usize, usize)]) {
+    let expected: HashSet<(usize, usize)> = expected.iter().copied().collect();
+    assert_eq!(harness.player_attempt().board.drawn_connections(), &expected);
+    assert_eq!(harness.connection_entity_pairs(), expected);
+}
+
+#[test]
+fn undo_and_redo_keep_entities_and_board_in_sync() {
+    let mut harness = TestHarness::new();
+    harness.start_level(1);
+
+    harness.connect(0, 2);
+    harness.step(1);
+    harness.connect(3, 2);
+    harness.step(2);
+    assert_in_sync(&mut harness, &[(0, 2), (2, 3)]);
+
+    harness.press_keys(&UNDO);
+    assert_in_sync(&mut harness, &[(0, 2)]);
+
+    harness.press_keys(&UNDO);
+    assert_in_sync(&mut harness, &[]);
+
+    // Nothing left to undo
+    harness.press_keys(&UNDO);
+    assert_in_sync(&mut harness, &[]);
+
+    harness.press_keys(&REDO);
+    assert_in_sync(&mut harness, &[(0, 2)]);
+
+    harness.press_keys(&REDO);
+    assert_in_sync(&mut harness, &[(0, 2), (2, 3)]);
+    assert_eq!(harness.state(), GameState::Playing);
+}
+
+#[test]
+fn new_connection_clears_redo() {
+    let mut harness = TestHarness::new();
+    harness.start_level(1);
+
+    harness.connect(0, 2);
+    harness.step(2);
+    harness.press_keys(&UNDO);
+    assert!(harness.player_attempt().history.can_redo());
+
+    harness.connect(1, 2);
+    harness.step(2);
+    assert!(!harness.player_attempt().history.can_redo());
+
+    harness.press_keys(&REDO);
+    assert_in_sync(&mut harness, &[(1, 2)]);
+}
+
+#[test]
+fn undo_without_ctrl_does_nothing() {
+    let mut harness = TestHarness::new();
+    harness.start_level(0);
+
+    harness.connect(0, 1);
+    harness.step(2);
+    harness.press_keys(&[KeyCode::KeyZ]);
+    assert_in_sync(&mut harness, &[(0, 1)]);
+}
+
+#[test]
+fn level_completes_after_undo_and_redraw() {
+    let mut harness = TestHarness::new();
+    harness.start_level(0);
+
+    harness.connect(0, 1);
+    harness.step(2);
+    harness.press_keys(&UNDO);
+    harness.connect(1, 2);
+    harness.step(2);
+    // Redo is gone after a new connection, so the solution has to be redrawn
+    harness.connect(0, 1);
+    harness.step(4);
+
+    assert_eq!(harness.state(), GameState::LevelComplete);
+}
+
+#[test]
+fn undo_and_redo_wait_while_the_board_refuses_them() {
+    // 300 and 500 units of cable respectively; only one fits the budget at a time
+    let mut board = PuzzleBoard::new(&PuzzleSpec {
+        title: "Budget".to_string(),
+        node_positions: vec![Vec2::new(0.0, 0.0), Vec2::new(300.0, 0.0), Vec2::new(0.0, 400.0)],
+        correct_connections: [(0, 1)].into_iter().collect(),
+        cable_budget: Some(600.0),
+        ..PuzzleSpec::default()
+    });
+    let mut history = ActionHistory::default();
+    board.try_connect(0, 1).unwrap();
+    history.record(BoardAction::Connect(0, 1));
+    board.disconnect(0, 1);
+    history.record(BoardAction::Disconnect(0, 1));
+
+    // A connection the history does not know about takes up the cable
+    board.try_connect(1, 2).unwrap();
+    assert_eq!(history.undo(&mut board), None);
+    assert_eq!(board.drawn_connections(), &[(1, 2)].into_iter().collect());
+    assert!(history.can_undo());
+    assert!(!history.can_redo());
+
+    board.disconnect(1, 2);
+    assert_eq!(history.undo(&mut board), Some(BoardAction::Disconnect(0, 1)));
+    assert_eq!(history.undo(&mut board), Some(BoardAction::Connect(0, 1)));
+    assert!(board.drawn_connections().is_empty());
+
+    board.try_connect(1, 2).unwrap();
+    assert_eq!(history.redo(&mut board), None);
+    assert_eq!(board.drawn_connections(), &[(1, 2)].into_iter().collect());
+    assert!(history.can_redo());
+
+    board.disconnect(1, 2);
+    assert_eq!(history.redo(&mut board), Some(BoardAction::Connect(0, 1)));
+    assert_eq!(board.drawn_connections(), &[(0, 1)].into_iter().collect());
+}
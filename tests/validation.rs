@@ -0,0 +1,101 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use bevy::math::Vec2;
+use common::triangle_spec;
+use echonet_game::resources::PuzzleSpec;
+use echonet_game::validation::{check_duplicate_connections, check_level, LevelIssue};
+
+fn triangle_with(connections: &[(usize, usize)]) -> PuzzleSpec {
+    PuzzleSpec { correct_connections: connections.iter().copied().collect(), ..triangle_spec() }
+}
+
+fn messages(issues: &[LevelIssue], field: &str) -> Vec<String> {
+    issues.iter().filter(|issue| issue.field == field).map(|issue| issue.message.clone()).collect()
+}
+
+#[test]
+fn a_well_formed_level_has_no_issues() {
+    assert_eq!(check_level(&triangle_with(&[(0, 1), (1, 2)])), Vec::new());
+}
+
+#[test]
+fn duplicate_pairs_are_found_in_either_orientation() {
+    assert!(check_duplicate_connections(&[(0, 1), (1, 2)]).is_empty());
+
+    let issues = check_duplicate_connections(&[(0, 1), (1, 2), (0, 1)]);
+    assert_eq!(messages(&issues, "correct_connections"), vec!["(0, 1) duplicates (0, 1)"]);
+
+    let issues = check_duplicate_connections(&[(0, 1), (1, 2), (2, 1)]);
+    assert_eq!(messages(&issues, "correct_connections"), vec!["(2, 1) duplicates (1, 2)"]);
+}
+
+#[test]
+fn self_loops_are_reported() {
+    let issues = check_level(&triangle_with(&[(0, 1), (1, 2), (2, 2)]));
+    assert_eq!(messages(&issues, "correct_connections"), vec!["(2, 2) connects a node to itself"]);
+}
+
+#[test]
+fn overlapping_nodes_are_reported() {
+    let mut spec = triangle_with(&[(0, 1), (1, 2)]);
+    spec.node_positions[2] = Vec2::new(110.0, 10.0);
+    let overlaps = messages(&check_level(&spec), "node_positions");
+    assert_eq!(overlaps.len(), 1);
+    assert!(overlaps[0].starts_with("nodes 1 and 2 are 14.1 units apart"), "{}", overlaps[0]);
+}
+
+#[test]
+fn nodes_the_solution_never_reaches_are_reported() {
+    let issues = check_level(&triangle_with(&[(0, 1)]));
+    assert_eq!(messages(&issues, "correct_connections"), vec!["nodes [2] cannot be reached from node 0"]);
+}
+
+// A fresh directory under the system temp dir for one test's level files
+fn level_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("echonet_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn validate_levels_exits_non_zero_when_a_level_is_broken() {
+    let dir = level_dir("validate_levels");
+    fs::write(
+        dir.join("level_01.ron"),
+        r#"(title: "Fine", node_positions: [(-100.0, 0.0), (100.0, 0.0)], correct_connections: [(0, 1)])"#,
+    )
+    .unwrap();
+    let run = || Command::new(env!("CARGO_BIN_EXE_validate_levels")).arg(&dir).output().unwrap();
+    assert!(run().status.success());
+
+    fs::write(
+        dir.join("level_02.ron"),
+        r#"(title: "Loop", node_positions: [(-100.0, 0.0), (100.0, 0.0)], correct_connections: [(0, 1), (1, 1)])"#,
+    )
+    .unwrap();
+    let output = run();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("level_02.ron"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn validate_levels_solve_says_match_connections_levels_are_not_searched() {
+    let dir = level_dir("validate_levels_solve");
+    fs::write(
+        dir.join("level_01.ron"),
+        r#"(title: "Fine", node_positions: [(-100.0, 0.0), (100.0, 0.0)], correct_connections: [(0, 1)])"#,
+    )
+    .unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_validate_levels")).arg("solve").arg(&dir).output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("1 MatchConnections level(s) skipped for uniqueness"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}